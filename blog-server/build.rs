@@ -2,6 +2,10 @@ fn main() -> std::io::Result<()> {
 
     tonic_build::configure()
         .type_attribute(".", "#[derive(serde::Serialize, serde::Deserialize)]")
+        // У FieldMask из prost-types нет serde
+        .field_attribute("blog.PatchPostRequest.update_mask", "#[serde(skip)]")
         .compile(&["proto/blog.proto"], &["proto"])?;
+    // sqlx::migrate!() встраивает миграции при компиляции, а новый файл сам пересборку не вызывает
+    println!("cargo:rerun-if-changed=migrations");
     Ok(())
 }
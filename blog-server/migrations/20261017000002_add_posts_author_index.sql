@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS idx_posts_author_id_created_at ON posts (author_id, created_at DESC, id DESC);
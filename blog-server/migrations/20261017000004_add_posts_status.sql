@@ -0,0 +1,12 @@
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS status VARCHAR(16) NOT NULL DEFAULT 'published'
+        CHECK (status IN ('draft', 'published', 'archived'));
+
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS published_at TIMESTAMPTZ;
+
+UPDATE posts SET published_at = created_at WHERE status = 'published' AND published_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_posts_published_feed
+    ON posts (created_at DESC, id DESC)
+    WHERE status = 'published';
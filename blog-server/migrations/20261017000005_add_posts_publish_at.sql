@@ -0,0 +1,6 @@
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS publish_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_posts_publish_at
+    ON posts (publish_at)
+    WHERE status = 'draft' AND publish_at IS NOT NULL;
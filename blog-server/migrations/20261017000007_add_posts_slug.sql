@@ -0,0 +1,18 @@
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS slug VARCHAR(96);
+
+-- Для уже существующих постов транслитерация недоступна в SQL, поэтому slug временный
+UPDATE posts SET slug = 'post-' || id WHERE slug IS NULL;
+
+ALTER TABLE posts
+    ALTER COLUMN slug SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_posts_slug ON posts (slug);
+
+CREATE TABLE IF NOT EXISTS post_slug_history (
+    slug VARCHAR(96) PRIMARY KEY,
+    post_id BIGINT NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_post_slug_history_post_id ON post_slug_history (post_id);
@@ -0,0 +1,3 @@
+-- Кэш отрендеренного Markdown; NULL заполняется фоновой задачей при старте сервера
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS content_html TEXT;
@@ -0,0 +1,14 @@
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS comment_mode VARCHAR(16) NOT NULL DEFAULT 'open'
+        CHECK (comment_mode IN ('open', 'moderated', 'closed'));
+
+ALTER TABLE comments
+    ADD COLUMN IF NOT EXISTS status VARCHAR(16) NOT NULL DEFAULT 'approved'
+        CHECK (status IN ('pending', 'approved', 'rejected', 'spam')),
+    ADD COLUMN IF NOT EXISTS moderation_reason TEXT,
+    ADD COLUMN IF NOT EXISTS moderated_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS moderated_at TIMESTAMPTZ;
+
+-- Очередь модерации автора поста
+CREATE INDEX IF NOT EXISTS idx_comments_pending ON comments (post_id, created_at)
+    WHERE status = 'pending' AND deleted_at IS NULL;
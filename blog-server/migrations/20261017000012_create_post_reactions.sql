@@ -0,0 +1,13 @@
+-- Одна реакция каждого вида от пользователя; счётчики считаются по строкам,
+-- поэтому конкурентные PUT/DELETE не могут их рассинхронизировать
+CREATE TABLE IF NOT EXISTS post_reactions (
+    post_id BIGINT NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(16) NOT NULL
+        CHECK (kind IN ('like', 'love', 'laugh', 'wow', 'sad', 'fire')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (post_id, user_id, kind)
+);
+
+CREATE INDEX IF NOT EXISTS idx_post_reactions_post_kind ON post_reactions (post_id, kind);
+CREATE INDEX IF NOT EXISTS idx_post_reactions_user ON post_reactions (user_id, post_id);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS follows (
+    follower_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    followee_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (follower_id, followee_id),
+    CHECK (follower_id <> followee_id)
+);
+
+-- Списки подписок и подписчиков отдаются от новых к старым
+CREATE INDEX IF NOT EXISTS idx_follows_follower ON follows (follower_id, created_at DESC, followee_id DESC);
+CREATE INDEX IF NOT EXISTS idx_follows_followee ON follows (followee_id, created_at DESC, follower_id DESC);
+
+-- Лента подписок: для каждого автора берутся его свежие опубликованные посты
+CREATE INDEX IF NOT EXISTS idx_posts_author_published
+    ON posts (author_id, published_at DESC, id DESC)
+    WHERE status = 'published';
@@ -0,0 +1,11 @@
+-- Удалённые посты остаются в корзине до окончательной очистки фоновой задачей
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_posts_trash
+    ON posts (author_id, deleted_at DESC)
+    WHERE deleted_at IS NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_posts_deleted_at
+    ON posts (deleted_at)
+    WHERE deleted_at IS NOT NULL;
@@ -0,0 +1,3 @@
+-- Номер версии для оптимистичной блокировки: растёт при каждом изменении поста
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS version INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,37 @@
+-- Время последнего изменения строки поддерживает триггер, а не приложение
+CREATE OR REPLACE FUNCTION set_updated_at() RETURNS trigger AS $$
+BEGIN
+    NEW.updated_at := now();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+ALTER TABLE posts
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+-- Для существующих постов лучшее приближение — время последней ревизии или публикации
+UPDATE posts
+SET updated_at = GREATEST(
+    created_at,
+    published_at,
+    (SELECT MAX(r.created_at) FROM post_revisions r WHERE r.post_id = posts.id)
+);
+UPDATE users SET updated_at = created_at;
+
+DROP TRIGGER IF EXISTS posts_set_updated_at ON posts;
+CREATE TRIGGER posts_set_updated_at
+    BEFORE UPDATE ON posts
+    FOR EACH ROW
+    EXECUTE FUNCTION set_updated_at();
+
+DROP TRIGGER IF EXISTS users_set_updated_at ON users;
+CREATE TRIGGER users_set_updated_at
+    BEFORE UPDATE ON users
+    FOR EACH ROW
+    EXECUTE FUNCTION set_updated_at();
+
+CREATE INDEX IF NOT EXISTS idx_posts_updated_at
+    ON posts (updated_at DESC, id DESC)
+    WHERE deleted_at IS NULL;
@@ -0,0 +1,12 @@
+-- Загруженные файлы: содержимое лежит во внешнем хранилище, здесь — метаданные
+CREATE TABLE IF NOT EXISTS media (
+    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    owner_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    key TEXT NOT NULL UNIQUE,
+    mime_type TEXT NOT NULL,
+    size BIGINT NOT NULL CHECK (size > 0),
+    checksum CHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_media_owner ON media (owner_id, created_at DESC);
@@ -0,0 +1,22 @@
+-- Уменьшенные копии картинок: строятся в фоне после загрузки оригинала
+ALTER TABLE media
+    ADD COLUMN IF NOT EXISTS width INT,
+    ADD COLUMN IF NOT EXISTS height INT,
+    -- Уже загруженные файлы тоже обработаются после перезапуска сервера
+    ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'processing'
+        CHECK (status IN ('processing', 'ready', 'failed'));
+
+CREATE TABLE IF NOT EXISTS media_variants (
+    media_id BIGINT NOT NULL REFERENCES media(id) ON DELETE CASCADE,
+    -- Имя размера из настроек: thumbnail, medium, large
+    name TEXT NOT NULL,
+    mime_type TEXT NOT NULL,
+    key TEXT NOT NULL UNIQUE,
+    width INT NOT NULL CHECK (width > 0),
+    height INT NOT NULL CHECK (height > 0),
+    size BIGINT NOT NULL CHECK (size > 0),
+    checksum CHAR(64) NOT NULL,
+    PRIMARY KEY (media_id, name, mime_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_media_processing ON media (id) WHERE status = 'processing';
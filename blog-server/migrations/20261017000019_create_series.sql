@@ -0,0 +1,25 @@
+-- Серии: упорядоченные наборы постов одного автора, например многосерийные туториалы
+CREATE TABLE IF NOT EXISTS series (
+    id BIGINT GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    owner_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_series_owner ON series (owner_id);
+
+DROP TRIGGER IF EXISTS series_set_updated_at ON series;
+CREATE TRIGGER series_set_updated_at
+    BEFORE UPDATE ON series
+    FOR EACH ROW
+    EXECUTE FUNCTION set_updated_at();
+
+-- Пост входит не больше чем в одну серию; позиции нумеруются с 1
+CREATE TABLE IF NOT EXISTS series_posts (
+    post_id BIGINT PRIMARY KEY REFERENCES posts(id) ON DELETE CASCADE,
+    series_id BIGINT NOT NULL REFERENCES series(id) ON DELETE CASCADE,
+    position INT NOT NULL CHECK (position > 0),
+    UNIQUE (series_id, position)
+);
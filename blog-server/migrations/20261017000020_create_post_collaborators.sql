@@ -0,0 +1,12 @@
+-- Соавторы поста; сам автор (posts.author_id) всегда владелец и в таблицу не попадает
+CREATE TABLE IF NOT EXISTS post_collaborators (
+    post_id BIGINT NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    user_id BIGINT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(16) NOT NULL CHECK (role IN ('owner', 'editor', 'viewer')),
+    invited_by BIGINT REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (post_id, user_id)
+);
+
+-- Черновики, доступные пользователю как соавтору, в списках и поиске
+CREATE INDEX IF NOT EXISTS idx_post_collaborators_user ON post_collaborators (user_id, post_id);
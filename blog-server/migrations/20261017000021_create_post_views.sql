@@ -0,0 +1,18 @@
+-- Дневные счётчики просмотров: все просмотры и уникальные посетители за день
+CREATE TABLE IF NOT EXISTS post_view_stats (
+    post_id BIGINT NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    views BIGINT NOT NULL DEFAULT 0,
+    visitors BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (post_id, day)
+);
+
+-- Анонимные хэши посетителей для дедупликации в пределах суток; старые дни удаляются
+CREATE TABLE IF NOT EXISTS post_view_visitors (
+    post_id BIGINT NOT NULL REFERENCES posts(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    visitor_hash TEXT NOT NULL,
+    PRIMARY KEY (post_id, day, visitor_hash)
+);
+
+CREATE INDEX IF NOT EXISTS idx_post_view_visitors_day ON post_view_visitors (day);
@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+
+use chrono::{Duration, NaiveDate, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::application::blog_service::role_of;
+use crate::data::analytics_repository::AnalyticsRepository;
+use crate::data::post_repository::PostRepository;
+use crate::domain::analytics::{PostStats, ViewKey, ViewTotals, fill_daily, is_bot, stats_range};
+use crate::domain::collaborator::Permission;
+use crate::domain::error::PostError;
+use crate::domain::post::{Post, PostStatus};
+use crate::presentation::auth::AuthenticatedUser;
+
+// Сколько разных посетителей помещается в буфер между сбросами; сверх этого просмотры теряются
+pub const MAX_PENDING_VIEWS: usize = 100_000;
+
+pub struct AnalyticsService<A, P>
+where
+    A: AnalyticsRepository + 'static,
+    P: PostRepository + 'static,
+{
+    repo: Arc<A>,
+    posts: Arc<P>,
+    secret: Vec<u8>,
+    pending: Mutex<HashMap<ViewKey, i64>>,
+}
+
+impl<A, P> AnalyticsService<A, P>
+where
+    A: AnalyticsRepository + 'static,
+    P: PostRepository + 'static,
+{
+    pub fn new(repo: Arc<A>, posts: Arc<P>, secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            repo,
+            posts,
+            secret: secret.into(),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Просмотр только копится в памяти, в базу его пишет фоновая задача.
+    // Считаются опубликованные посты без ботов и без просмотров самого автора
+    pub fn record_view(
+        &self,
+        post: &Post,
+        viewer_id: Option<i64>,
+        ip: Option<IpAddr>,
+        user_agent: Option<&str>,
+    ) {
+        if post.status != PostStatus::Published
+            || viewer_id == Some(post.author_id)
+            || is_bot(user_agent)
+        {
+            return;
+        }
+        let day = Utc::now().date_naive();
+        let key = ViewKey {
+            post_id: post.id,
+            day,
+            visitor_hash: self.visitor_hash(day, viewer_id, ip, user_agent),
+        };
+        let mut pending = self.pending.lock().unwrap();
+        if let Some(views) = pending.get_mut(&key) {
+            *views += 1;
+        } else if pending.len() < MAX_PENDING_VIEWS {
+            pending.insert(key, 1);
+        } else {
+            tracing::warn!(post_id = post.id, "view buffer is full, view dropped");
+        }
+    }
+
+    // Возвращает число записанных просмотров
+    pub async fn flush_views(&self) -> Result<i64, PostError> {
+        let batch: Vec<(ViewKey, i64)> = std::mem::take(&mut *self.pending.lock().unwrap())
+            .into_iter()
+            .collect();
+        if batch.is_empty() {
+            return Ok(0);
+        }
+        if let Err(e) = self.repo.record_views(&batch).await {
+            // Запись атомарна, поэтому при ошибке буфер возвращается целиком до следующей попытки
+            let mut pending = self.pending.lock().unwrap();
+            for (key, views) in batch {
+                *pending.entry(key).or_default() += views;
+            }
+            return Err(e);
+        }
+        Ok(batch.iter().map(|(_, views)| views).sum())
+    }
+
+    // Вчерашние хэши ещё нужны просмотрам, сброшенным сразу после полуночи
+    pub async fn prune_visitors(&self) -> Result<u64, PostError> {
+        let before = Utc::now().date_naive() - Duration::days(1);
+        self.repo.prune_visitors(before).await
+    }
+
+    pub async fn post_stats(
+        &self,
+        id: i64,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+        current_user: AuthenticatedUser,
+    ) -> Result<PostStats, PostError> {
+        let (from, to) = stats_range(from, to, Utc::now().date_naive())?;
+        let post = self
+            .posts
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("post {} not found", id)))?;
+        match role_of(self.posts.as_ref(), &post, Some(current_user.id)).await? {
+            Some(role) if role.allows(Permission::Manage) => {}
+            _ => return Err(PostError::Forbidden),
+        }
+        let daily = fill_daily(from, to, self.repo.daily_views(id, from, to).await?);
+        let totals = daily
+            .iter()
+            .fold(ViewTotals::default(), |acc, day| ViewTotals {
+                views: acc.views + day.views,
+                visitors: acc.visitors + day.visitors,
+            });
+        let all_time = self.repo.total_views(id).await?;
+        Ok(PostStats {
+            post_id: id,
+            from,
+            to,
+            totals,
+            all_time,
+            daily,
+        })
+    }
+
+    // Хэш меняется каждые сутки и без секрета сервера не сопоставляется с IP,
+    // поэтому посетителя нельзя отследить между днями. Вошедший пользователь
+    // узнаётся по id, аноним — по IP и User-Agent
+    fn visitor_hash(
+        &self,
+        day: NaiveDate,
+        viewer_id: Option<i64>,
+        ip: Option<IpAddr>,
+        user_agent: Option<&str>,
+    ) -> String {
+        let visitor = match viewer_id {
+            Some(id) => format!("user:{}", id),
+            None => format!(
+                "anon:{}\n{}",
+                ip.map(|ip| ip.to_string()).unwrap_or_default(),
+                user_agent.unwrap_or_default()
+            ),
+        };
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts keys of any length");
+        mac.update(format!("post-views\n{}\n{}", day, visitor).as_bytes());
+        hex::encode(&mac.finalize().into_bytes()[..16])
+    }
+}
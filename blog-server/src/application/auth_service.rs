@@ -27,8 +27,7 @@ where
     pub async fn get_user(&self, id: i64) -> Result<User, AuthError> {
         self.repo
             .find_by_id(id)
-            .await
-            .map_err(AuthError::from)?
+            .await?
             .ok_or_else(|| AuthError::UserNotFound(format!("user {}", id)))
     }
 
@@ -41,7 +40,7 @@ where
     ) -> Result<User, AuthError> {
         let hash = hash_password(&password).map_err(|err| AuthError::Internal(err.to_string()))?;
         let user = NewUser::new(email.to_lowercase(), username, hash);
-        self.repo.create(user).await.map_err(AuthError::from)
+        self.repo.create(user).await
     }
 
     #[instrument(skip(self))]
@@ -50,9 +49,8 @@ where
         let user = self
             .repo
             .find_by_email(&email.to_lowercase())
-            .await
-            .map_err(AuthError::from)?
-            .ok_or_else(|| DomainError::Unauthorized)?;
+            .await?
+            .ok_or(DomainError::Unauthorized)?;
 
         let valid = verify_password(password, &user.password_hash)
             .map_err(|_| DomainError::Unauthorized)?;
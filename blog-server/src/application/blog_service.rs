@@ -1,13 +1,33 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Duration, Utc};
+
 use crate::data::post_repository::PostRepository;
-use crate::domain::post::NewPost;
+use crate::domain::collaborator::{Collaborator, Permission, PostRole};
+use crate::domain::markdown::render_markdown;
+use crate::domain::post::{
+    CommentMode, NewPost, PostPatch, PostStatus, SlugLookup, TrashedPost, validate_content,
+    validate_title,
+};
+use crate::domain::post_query::{PostCursor, PostListParams, PostPage, PostQuery};
+use crate::domain::post_search::{PostSearchPage, PostSearchQuery, SearchCursor};
+use crate::domain::reaction::ReactionKind;
+use crate::domain::revision::{PostRevision, RevisionDiff};
+use crate::domain::tag::normalize_tags;
 use crate::domain::{error::PostError, post::Post};
 use crate::presentation::auth::AuthenticatedUser;
 
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
+pub const MAX_PAGE_SIZE: u32 = 100;
+pub const PUBLISH_BATCH_SIZE: i64 = 50;
+pub const RENDER_BATCH_SIZE: i64 = 100;
+pub const PURGE_BATCH_SIZE: i64 = 100;
+pub const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+
 #[derive(Clone)]
 pub struct PostService<R: PostRepository + 'static> {
     repo: Arc<R>,
+    trash_retention: Duration,
 }
 
 impl<R> PostService<R>
@@ -15,34 +35,134 @@ where
     R: PostRepository + 'static,
 {
     pub fn new(repo: Arc<R>) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            trash_retention: Duration::days(DEFAULT_TRASH_RETENTION_DAYS),
+        }
+    }
+
+    pub fn with_trash_retention(mut self, retention: Duration) -> Self {
+        self.trash_retention = retention;
+        self
     }
 
     pub async fn create_post(
         &self,
         title: String,
         content: String,
+        status: PostStatus,
+        tags: Vec<String>,
         author_id: i64,
     ) -> Result<Post, PostError> {
-        let post = NewPost::new(title, content, author_id);
-        let new_post = self.repo.create(post).await.map_err(PostError::from);
-        new_post
+        if status == PostStatus::Archived {
+            return Err(PostError::Validation(
+                "a new post cannot be archived".into(),
+            ));
+        }
+        validate_title(&title)?;
+        validate_content(&content)?;
+        let tags = normalize_tags(&tags)?;
+        let content_html = render_markdown(&content);
+        let post = NewPost::new(title, content, author_id)
+            .with_status(status)
+            .with_tags(Some(tags))
+            .with_content_html(content_html);
+        self.repo.create(post).await
     }
 
-    pub async fn get_posts(&self) -> Result<Vec<Post>, PostError> {
-        self.repo
-            .find_all()
-            .await
-            .map_err(PostError::from)?
-            .ok_or_else(|| PostError::PostNotFound("posts not found".into()))
+    pub async fn get_posts(&self, params: PostListParams) -> Result<PostPage, PostError> {
+        let limit = params
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE) as usize;
+        let viewer_id = params.viewer_id;
+        // Берём на одну запись больше, чтобы понять, есть ли следующая страница
+        let query = PostQuery::from_params(params, limit as i64 + 1)?;
+
+        let mut posts = self.repo.find_page(&query).await?;
+        let next_cursor = if posts.len() > limit {
+            posts.truncate(limit);
+            posts
+                .last()
+                .map(|p| PostCursor::new(p, &query.sort).encode())
+        } else {
+            None
+        };
+        self.mark_my_reactions(&mut posts, viewer_id).await?;
+        Ok(PostPage { posts, next_cursor })
     }
 
-    pub async fn get_post(&self, id: i64) -> Result<Post, PostError> {
-        self.repo
-            .find_by_id(id)
-            .await
-            .map_err(PostError::from)?
-            .ok_or_else(|| PostError::PostNotFound(format!("post {} not found", id)))
+    // Опубликованные посты авторов, на которых подписан пользователь, от новых к старым
+    pub async fn get_feed(
+        &self,
+        user_id: i64,
+        cursor: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<PostPage, PostError> {
+        let params = PostListParams {
+            viewer_id: Some(user_id),
+            followed_by: Some(user_id),
+            status: Some(PostStatus::Published.as_str().to_string()),
+            // Пост, опубликованный из давнего черновика, попадает в начало ленты
+            sort: Some("-published_at".into()),
+            cursor,
+            limit,
+            ..Default::default()
+        };
+        self.get_posts(params).await
+    }
+
+    pub async fn search_posts(
+        &self,
+        text: &str,
+        viewer_id: Option<i64>,
+        cursor: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<PostSearchPage, PostError> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as usize;
+        let query = PostSearchQuery::new(text, viewer_id, cursor, limit as i64 + 1)?;
+
+        let mut hits = self.repo.search(&query).await?;
+        let next_cursor = if hits.len() > limit {
+            hits.truncate(limit);
+            hits.last().map(|h| SearchCursor::from(h).encode())
+        } else {
+            None
+        };
+        self.mark_my_reactions(hits.iter_mut().map(|h| &mut h.post), viewer_id)
+            .await?;
+        Ok(PostSearchPage { hits, next_cursor })
+    }
+
+    pub async fn get_post(&self, id: i64, viewer_id: Option<i64>) -> Result<Post, PostError> {
+        let post = self.find_visible_post(id, viewer_id).await?;
+        self.with_my_reactions(post, viewer_id).await
+    }
+
+    pub async fn get_post_by_slug(
+        &self,
+        slug: &str,
+        viewer_id: Option<i64>,
+    ) -> Result<SlugLookup, PostError> {
+        let not_found = || PostError::PostNotFound(format!("post {} not found", slug));
+        if let Some(post) = self.repo.find_by_slug(slug).await? {
+            if !self.can_view(&post, viewer_id).await? {
+                return Err(not_found());
+            }
+            return Ok(SlugLookup::Current(
+                self.with_my_reactions(post, viewer_id).await?,
+            ));
+        }
+        // Для редиректа достаточно актуального slug, реакции не нужны
+        let post = self
+            .repo
+            .find_by_old_slug(slug)
+            .await?
+            .ok_or_else(not_found)?;
+        if !self.can_view(&post, viewer_id).await? {
+            return Err(not_found());
+        }
+        Ok(SlugLookup::Moved(post))
     }
 
     pub async fn update_post(
@@ -50,36 +170,771 @@ where
         id: i64,
         title: String,
         content: String,
+        tags: Option<Vec<String>>,
+        expected_versions: Option<Vec<i32>>,
         current_user: AuthenticatedUser,
     ) -> Result<Post, PostError> {
-        let post = self.repo.find_by_id(id).await.map_err(PostError::from)?;
-        if post.is_none() {
-            return Err(PostError::PostNotFound(format!("post {} not found", id)));
+        validate_title(&title)?;
+        validate_content(&content)?;
+        let tags = tags.as_deref().map(normalize_tags).transpose()?;
+        self.authorize(id, &current_user, Permission::Edit).await?;
+        // Автором ревизии записывается тот, кто правит, а не автор поста
+        let content_html = render_markdown(&content);
+        let post = NewPost::new(title, content, current_user.id)
+            .with_tags(tags)
+            .with_content_html(content_html);
+        let post = self
+            .repo
+            .update(id, post, expected_versions)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("post {} not found", id)))?;
+        self.with_my_reactions(post, Some(current_user.id)).await
+    }
+
+    pub async fn patch_post(
+        &self,
+        id: i64,
+        patch: PostPatch,
+        expected_versions: Option<Vec<i32>>,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        if patch.is_empty() {
+            return Err(PostError::Validation("nothing to update".into()));
         }
-        if post.unwrap().author_id != current_user.id {
-            return Err(PostError::Forbidden);
+        patch.validate()?;
+        let patch = PostPatch {
+            tags: patch.tags.as_deref().map(normalize_tags).transpose()?,
+            content_html: patch.content.as_deref().map(render_markdown),
+            ..patch
+        };
+        self.authorize(id, &current_user, Permission::Edit).await?;
+        let post = self
+            .repo
+            .patch(id, &patch, current_user.id, expected_versions)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("post {} not found", id)))?;
+        self.with_my_reactions(post, Some(current_user.id)).await
+    }
+
+    pub async fn delete_post(
+        &self,
+        id: i64,
+        expected_versions: Option<Vec<i32>>,
+        current_user: AuthenticatedUser,
+    ) -> Result<(), PostError> {
+        let post = self
+            .authorize(id, &current_user, Permission::Manage)
+            .await?;
+        if let Some(expected) = &expected_versions
+            && !expected.contains(&post.version)
+        {
+            return Err(PostError::PreconditionFailed(format!(
+                "post {} is at version {}, not {:?}",
+                id, post.version, expected
+            )));
         }
-        let post = NewPost::new(title, content, current_user.id);
+        // Пост мог измениться или попасть в корзину между проверкой и удалением
         self.repo
-            .update(id, post)
+            .delete(id, expected_versions.clone())
+            .await?
+            .ok_or_else(|| match expected_versions {
+                Some(_) => PostError::PreconditionFailed(format!("post {} has been modified", id)),
+                None => PostError::PostNotFound(format!("post {} not found", id)),
+            })?;
+        Ok(())
+    }
+
+    pub async fn list_trash(
+        &self,
+        current_user: AuthenticatedUser,
+    ) -> Result<Vec<TrashedPost>, PostError> {
+        let posts = self.repo.find_trash(current_user.id).await?;
+        Ok(posts
+            .into_iter()
+            .filter_map(|post| {
+                let purge_at = post.deleted_at? + self.trash_retention;
+                Some(TrashedPost { post, purge_at })
+            })
+            .collect())
+    }
+
+    // Восстановить пост может только владелец и только пока не истёк срок хранения в корзине
+    pub async fn restore_post(
+        &self,
+        id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        let post = self
+            .repo
+            .restore(id, current_user.id, Utc::now() - self.trash_retention)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("post {} not found in trash", id)))?;
+        self.with_my_reactions(post, Some(current_user.id)).await
+    }
+
+    // Окончательно удаляет посты, пролежавшие в корзине дольше срока хранения; вызывается фоновой задачей
+    pub async fn purge_expired_posts(&self) -> Result<u64, PostError> {
+        let deleted_before = Utc::now() - self.trash_retention;
+        let mut total = 0;
+        loop {
+            let purged = self
+                .repo
+                .purge_deleted(deleted_before, PURGE_BATCH_SIZE)
+                .await?;
+            total += purged;
+            if purged < PURGE_BATCH_SIZE as u64 {
+                return Ok(total);
+            }
+        }
+    }
+
+    pub async fn publish_post(
+        &self,
+        id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        self.change_status(id, PostStatus::Published, current_user)
             .await
-            .map_err(PostError::from)?
-            .ok_or_else(|| PostError::PostNotFound(format!("post {} not found", id)))
     }
 
-    pub async fn delete_post(
+    pub async fn unpublish_post(
+        &self,
+        id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        self.change_status(id, PostStatus::Draft, current_user)
+            .await
+    }
+
+    pub async fn archive_post(
+        &self,
+        id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        self.change_status(id, PostStatus::Archived, current_user)
+            .await
+    }
+
+    // publish_at = None снимает отложенную публикацию
+    pub async fn schedule_post(
+        &self,
+        id: i64,
+        publish_at: Option<DateTime<Utc>>,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        let post = self
+            .authorize(id, &current_user, Permission::Manage)
+            .await?;
+        if post.status != PostStatus::Draft {
+            return Err(PostError::InvalidState(format!(
+                "only drafts can be scheduled, post is {}",
+                post.status.as_str()
+            )));
+        }
+        if let Some(publish_at) = publish_at
+            && publish_at <= Utc::now()
+        {
+            return Err(PostError::Validation(
+                "publish_at must be in the future".into(),
+            ));
+        }
+        let post = self
+            .repo
+            .set_publish_at(id, publish_at)
+            .await?
+            .ok_or_else(|| PostError::InvalidState(format!("post {} is no longer a draft", id)))?;
+        self.with_my_reactions(post, Some(current_user.id)).await
+    }
+
+    pub async fn set_comment_mode(
+        &self,
+        id: i64,
+        mode: CommentMode,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        self.authorize(id, &current_user, Permission::Manage)
+            .await?;
+        let post = self
+            .repo
+            .set_comment_mode(id, mode)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("post {} not found", id)))?;
+        self.with_my_reactions(post, Some(current_user.id)).await
+    }
+
+    // Повторная реакция того же вида ничего не меняет, поэтому PUT идемпотентен
+    pub async fn react(
+        &self,
+        id: i64,
+        kind: ReactionKind,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        self.find_reactable_post(id, current_user.id).await?;
+        self.repo.add_reaction(id, current_user.id, kind).await?;
+        self.get_post(id, Some(current_user.id)).await
+    }
+
+    pub async fn unreact(
+        &self,
+        id: i64,
+        kind: ReactionKind,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        self.find_visible_post(id, Some(current_user.id)).await?;
+        self.repo.remove_reaction(id, current_user.id, kind).await?;
+        self.get_post(id, Some(current_user.id)).await
+    }
+
+    // Публикует черновики, у которых наступило время publish_at; вызывается фоновым воркером
+    pub async fn publish_due_posts(&self) -> Result<Vec<Post>, PostError> {
+        let mut published = Vec::new();
+        loop {
+            let batch = self.repo.publish_due(PUBLISH_BATCH_SIZE).await?;
+            let done = (batch.len() as i64) < PUBLISH_BATCH_SIZE;
+            published.extend(batch);
+            if done {
+                return Ok(published);
+            }
+        }
+    }
+
+    // Запрос поиска строится с текущим SEARCH_LANGUAGE, поэтому все посты
+    // должны быть проиндексированы с ним же
+    pub async fn reindex_search(&self) -> Result<u64, PostError> {
+        self.repo.reindex_search().await
+    }
+
+    // Посты, созданные до появления slug'ов, получают slug из заголовка,
+    // как если бы он был выдан при создании
+    pub async fn backfill_slugs(&self) -> Result<u64, PostError> {
+        self.repo.backfill_slugs().await
+    }
+
+    // Дорендеривает посты, созданные до появления content_html
+    pub async fn render_missing_html(&self) -> Result<usize, PostError> {
+        let mut rendered = 0;
+        loop {
+            let batch = self.repo.find_without_html(RENDER_BATCH_SIZE).await?;
+            for post in &batch {
+                self.repo
+                    .set_content_html(post.id, &render_markdown(&post.content))
+                    .await?;
+            }
+            rendered += batch.len();
+            if (batch.len() as i64) < RENDER_BATCH_SIZE {
+                return Ok(rendered);
+            }
+        }
+    }
+
+    async fn change_status(
+        &self,
+        id: i64,
+        status: PostStatus,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        let post = self
+            .authorize(id, &current_user, Permission::Manage)
+            .await?;
+        if !post.status.can_transition_to(status) {
+            return Err(PostError::InvalidState(format!(
+                "cannot change status from {} to {}",
+                post.status.as_str(),
+                status.as_str()
+            )));
+        }
+        let post = self
+            .repo
+            .set_status(id, status)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("post {} not found", id)))?;
+        self.with_my_reactions(post, Some(current_user.id)).await
+    }
+
+    pub async fn list_revisions(
+        &self,
+        id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<Vec<PostRevision>, PostError> {
+        self.authorize(id, &current_user, Permission::View).await?;
+        self.repo.list_revisions(id).await
+    }
+
+    pub async fn get_revision(
         &self,
         id: i64,
+        revision: i32,
         current_user: AuthenticatedUser,
+    ) -> Result<PostRevision, PostError> {
+        self.authorize(id, &current_user, Permission::View).await?;
+        self.find_revision(id, revision).await
+    }
+
+    pub async fn diff_revisions(
+        &self,
+        id: i64,
+        from: i32,
+        to: i32,
+        current_user: AuthenticatedUser,
+    ) -> Result<RevisionDiff, PostError> {
+        self.authorize(id, &current_user, Permission::View).await?;
+        let from = self.find_revision(id, from).await?;
+        let to = self.find_revision(id, to).await?;
+        Ok(RevisionDiff::between(&from, &to))
+    }
+
+    // Восстановление не переписывает историю: старая версия сохраняется новой ревизией
+    pub async fn restore_revision(
+        &self,
+        id: i64,
+        revision: i32,
+        current_user: AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        self.authorize(id, &current_user, Permission::Edit).await?;
+        let revision = self.find_revision(id, revision).await?;
+        // Теги не версионируются и при откате остаются текущими
+        self.update_post(
+            id,
+            revision.title,
+            revision.content,
+            None,
+            None,
+            current_user,
+        )
+        .await
+    }
+
+    pub async fn list_collaborators(
+        &self,
+        id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<Vec<Collaborator>, PostError> {
+        self.authorize(id, &current_user, Permission::View).await?;
+        self.repo.list_collaborators(id).await
+    }
+
+    // Повторное приглашение только меняет роль, поэтому PUT идемпотентен
+    pub async fn set_collaborator(
+        &self,
+        id: i64,
+        user_id: i64,
+        role: PostRole,
+        current_user: AuthenticatedUser,
+    ) -> Result<Collaborator, PostError> {
+        let post = self
+            .authorize(id, &current_user, Permission::Manage)
+            .await?;
+        if user_id == post.author_id {
+            return Err(PostError::InvalidState(
+                "the author is always an owner of the post".into(),
+            ));
+        }
+        self.repo
+            .upsert_collaborator(id, user_id, role, current_user.id)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("user {} not found", user_id)))
+    }
+
+    // Соавтор может выйти из поста сам, остальных убирает владелец
+    pub async fn remove_collaborator(
+        &self,
+        id: i64,
+        user_id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<(), PostError> {
+        let permission = if user_id == current_user.id {
+            Permission::View
+        } else {
+            Permission::Manage
+        };
+        let post = self.authorize(id, &current_user, permission).await?;
+        if user_id == post.author_id {
+            return Err(PostError::InvalidState(
+                "the author cannot be removed from the post".into(),
+            ));
+        }
+        if !self.repo.remove_collaborator(id, user_id).await? {
+            return Err(PostError::PostNotFound(format!(
+                "user {} is not a collaborator of post {}",
+                user_id, id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn find_revision(&self, id: i64, revision: i32) -> Result<PostRevision, PostError> {
+        self.repo.find_revision(id, revision).await?.ok_or_else(|| {
+            PostError::PostNotFound(format!("revision {} of post {} not found", revision, id))
+        })
+    }
+
+    async fn find_visible_post(&self, id: i64, viewer_id: Option<i64>) -> Result<Post, PostError> {
+        let not_found = || PostError::PostNotFound(format!("post {} not found", id));
+        let post = self.repo.find_by_id(id).await?.ok_or_else(not_found)?;
+        if !self.can_view(&post, viewer_id).await? {
+            return Err(not_found());
+        }
+        Ok(post)
+    }
+
+    // Неопубликованные посты видят автор и все соавторы
+    async fn can_view(&self, post: &Post, viewer_id: Option<i64>) -> Result<bool, PostError> {
+        if post.is_visible_to(viewer_id) {
+            return Ok(true);
+        }
+        Ok(role_of(self.repo.as_ref(), post, viewer_id)
+            .await?
+            .is_some())
+    }
+
+    // Реагировать можно только на опубликованные посты
+    async fn find_reactable_post(&self, id: i64, user_id: i64) -> Result<Post, PostError> {
+        let post = self.find_visible_post(id, Some(user_id)).await?;
+        if post.status != PostStatus::Published {
+            return Err(PostError::InvalidState(
+                "reactions are only allowed on published posts".into(),
+            ));
+        }
+        Ok(post)
+    }
+
+    // Проставляет флаг reacted одним запросом на всю выборку
+    async fn mark_my_reactions<'a>(
+        &self,
+        posts: impl IntoIterator<Item = &'a mut Post>,
+        viewer_id: Option<i64>,
     ) -> Result<(), PostError> {
-        let post = self.repo.find_by_id(id).await.map_err(PostError::from)?;
-        if post.is_none() {
-            return Err(PostError::PostNotFound(format!("post {} not found", id)));
+        let Some(viewer_id) = viewer_id else {
+            return Ok(());
+        };
+        let mut posts: Vec<&mut Post> = posts.into_iter().collect();
+        if posts.is_empty() {
+            return Ok(());
         }
-        if post.unwrap().author_id != current_user.id {
-            return Err(PostError::Forbidden);
+        let ids: Vec<i64> = posts.iter().map(|post| post.id).collect();
+        let mine = self.repo.find_user_reactions(viewer_id, &ids).await?;
+        for post in posts.iter_mut() {
+            let post_id = post.id;
+            for reaction in post.reactions.iter_mut() {
+                reaction.reacted = mine.contains(&(post_id, reaction.kind));
+            }
         }
-        let _ = self.repo.delete(id).await.map_err(PostError::from);
         Ok(())
     }
+
+    async fn with_my_reactions(
+        &self,
+        mut post: Post,
+        viewer_id: Option<i64>,
+    ) -> Result<Post, PostError> {
+        self.mark_my_reactions([&mut post], viewer_id).await?;
+        Ok(post)
+    }
+
+    async fn authorize(
+        &self,
+        id: i64,
+        current_user: &AuthenticatedUser,
+        permission: Permission,
+    ) -> Result<Post, PostError> {
+        let post = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("post {} not found", id)))?;
+        match role_of(self.repo.as_ref(), &post, Some(current_user.id)).await? {
+            Some(role) if role.allows(permission) => Ok(post),
+            _ => Err(PostError::Forbidden),
+        }
+    }
+}
+
+// Роль пользователя в посте: автор всегда владелец, остальные — по приглашению
+pub async fn role_of<R: PostRepository>(
+    repo: &R,
+    post: &Post,
+    user_id: Option<i64>,
+) -> Result<Option<PostRole>, PostError> {
+    match user_id {
+        None => Ok(None),
+        Some(user_id) if user_id == post.author_id => Ok(Some(PostRole::Owner)),
+        Some(user_id) => repo.find_collaborator_role(post.id, user_id).await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::memory::{MemoryPostRepository, post};
+    use crate::domain::post_query::PostSort;
+
+    const AUTHOR: i64 = 1;
+    const EDITOR: i64 = 2;
+    const VIEWER: i64 = 3;
+    const STRANGER: i64 = 4;
+
+    // Черновик автора с приглашёнными редактором и читателем
+    async fn with_draft() -> Arc<MemoryPostRepository> {
+        let repo = MemoryPostRepository::default();
+        repo.insert(post(1, AUTHOR, PostStatus::Draft));
+        for (user_id, role) in [(EDITOR, PostRole::Editor), (VIEWER, PostRole::Viewer)] {
+            repo.upsert_collaborator(1, user_id, role, AUTHOR)
+                .await
+                .unwrap();
+        }
+        Arc::new(repo)
+    }
+
+    fn user(id: i64) -> AuthenticatedUser {
+        AuthenticatedUser {
+            id,
+            email: format!("user{}@example.com", id),
+        }
+    }
+
+    #[tokio::test]
+    async fn roles_limit_what_collaborators_can_do() {
+        let service = PostService::new(with_draft().await);
+        for (id, permission, allowed) in [
+            (AUTHOR, Permission::Manage, true),
+            (EDITOR, Permission::Edit, true),
+            (EDITOR, Permission::Manage, false),
+            (VIEWER, Permission::View, true),
+            (VIEWER, Permission::Edit, false),
+            (STRANGER, Permission::View, false),
+        ] {
+            let result = service.authorize(1, &user(id), permission).await;
+            assert_eq!(result.is_ok(), allowed, "user {} {:?}", id, permission);
+            if !allowed {
+                assert!(matches!(result, Err(PostError::Forbidden)));
+            }
+        }
+        assert!(matches!(
+            service.authorize(2, &user(AUTHOR), Permission::View).await,
+            Err(PostError::PostNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn drafts_are_visible_only_to_collaborators() {
+        let service = PostService::new(with_draft().await);
+        let draft = service.repo.find_by_id(1).await.unwrap().unwrap();
+        for (viewer, visible) in [
+            (Some(AUTHOR), true),
+            (Some(EDITOR), true),
+            (Some(VIEWER), true),
+            (Some(STRANGER), false),
+            (None, false),
+        ] {
+            assert_eq!(service.can_view(&draft, viewer).await.unwrap(), visible);
+        }
+        assert!(matches!(
+            service.get_post(1, Some(STRANGER)).await,
+            Err(PostError::PostNotFound(_))
+        ));
+        assert_eq!(service.get_post(1, Some(VIEWER)).await.unwrap().id, 1);
+    }
+
+    #[tokio::test]
+    async fn only_owners_manage_collaborators() {
+        let service = PostService::new(with_draft().await);
+        assert!(matches!(
+            service
+                .set_collaborator(1, STRANGER, PostRole::Owner, user(EDITOR))
+                .await,
+            Err(PostError::Forbidden)
+        ));
+        assert!(matches!(
+            service
+                .set_collaborator(1, STRANGER, PostRole::Owner, user(STRANGER))
+                .await,
+            Err(PostError::Forbidden)
+        ));
+        assert!(matches!(
+            service.remove_collaborator(1, EDITOR, user(VIEWER)).await,
+            Err(PostError::Forbidden)
+        ));
+        assert!(matches!(
+            service
+                .set_collaborator(1, AUTHOR, PostRole::Viewer, user(AUTHOR))
+                .await,
+            Err(PostError::InvalidState(_))
+        ));
+
+        // Соавтор может выйти сам, после чего теряет доступ к черновику
+        service
+            .remove_collaborator(1, VIEWER, user(VIEWER))
+            .await
+            .unwrap();
+        assert!(service.get_post(1, Some(VIEWER)).await.is_err());
+
+        let invited = service
+            .set_collaborator(1, STRANGER, PostRole::Owner, user(AUTHOR))
+            .await
+            .unwrap();
+        assert_eq!(invited.invited_by, Some(AUTHOR));
+        let published = service.publish_post(1, user(STRANGER)).await.unwrap();
+        assert_eq!(published.status, PostStatus::Published);
+        assert!(service.unpublish_post(1, user(EDITOR)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn feed_pages_by_publication_time() {
+        let repo = MemoryPostRepository::default();
+        let published_at = Utc::now() - Duration::hours(1);
+        for id in [3, 2, 1] {
+            repo.insert(Post {
+                published_at: Some(published_at - Duration::minutes(id)),
+                ..post(id, AUTHOR, PostStatus::Published)
+            });
+        }
+        let service = PostService::new(Arc::new(repo));
+
+        let page = service.get_feed(STRANGER, None, Some(2)).await.unwrap();
+        assert_eq!(page.posts.len(), 2);
+        let query = service.repo.last_query().unwrap();
+        assert_eq!(query.filter.followed_by, Some(STRANGER));
+        assert_eq!(query.filter.status, Some(PostStatus::Published));
+        assert_eq!(PostSort::to_spec(&query.sort), "-published_at,-id");
+
+        let cursor = PostCursor::decode(page.next_cursor.as_deref().unwrap()).unwrap();
+        assert_eq!(cursor.id, 2);
+        assert_eq!(cursor.published_at, page.posts[1].published_at);
+
+        service
+            .get_feed(STRANGER, page.next_cursor, Some(2))
+            .await
+            .unwrap();
+        let query = service.repo.last_query().unwrap();
+        assert_eq!(query.after, Some(cursor));
+
+        assert!(
+            service
+                .get_feed(STRANGER, Some("garbage".into()), None)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn search_pages_by_rank() {
+        let repo = MemoryPostRepository::default();
+        for (id, title, content, status) in [
+            (1, "Rust async", "", PostStatus::Published),
+            (2, "Tokio", "Written in Rust", PostStatus::Published),
+            (3, "Rust draft", "", PostStatus::Draft),
+            (4, "Actix", "Rust web framework", PostStatus::Published),
+        ] {
+            repo.insert(Post {
+                title: title.into(),
+                content: content.into(),
+                ..post(id, AUTHOR, status)
+            });
+        }
+        let service = PostService::new(Arc::new(repo));
+
+        // Совпадения в заголовке выше, при равном ранге — сначала больший id
+        let mut found = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = service
+                .search_posts("rust", None, cursor.as_deref(), Some(1))
+                .await
+                .unwrap();
+            found.extend(page.hits.iter().map(|hit| hit.post.id));
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(found, vec![1, 4, 2]);
+
+        let page = service
+            .search_posts("rust", Some(AUTHOR), None, None)
+            .await
+            .unwrap();
+        assert_eq!(page.hits.len(), 4);
+        assert!(page.next_cursor.is_none());
+    }
+
+    #[tokio::test]
+    async fn publishes_only_due_drafts() {
+        let repo = MemoryPostRepository::default();
+        let now = Utc::now();
+        // Больше одной пачки, чтобы сервис запросил следующую
+        let due = PUBLISH_BATCH_SIZE + 1;
+        for id in 1..=due {
+            repo.insert(Post {
+                publish_at: Some(now - Duration::minutes(id)),
+                ..post(id, AUTHOR, PostStatus::Draft)
+            });
+        }
+        let future = due + 1;
+        repo.insert(Post {
+            publish_at: Some(now + Duration::hours(1)),
+            ..post(future, AUTHOR, PostStatus::Draft)
+        });
+        let trashed = due + 2;
+        repo.insert(Post {
+            publish_at: Some(now - Duration::hours(1)),
+            deleted_at: Some(now),
+            ..post(trashed, AUTHOR, PostStatus::Draft)
+        });
+        let service = PostService::new(Arc::new(repo));
+
+        let published = service.publish_due_posts().await.unwrap();
+        assert_eq!(published.len() as i64, due);
+        let first = service.repo.stored(1).unwrap();
+        assert_eq!(first.status, PostStatus::Published);
+        assert_eq!(first.published_at, Some(now - Duration::minutes(1)));
+        assert!(first.publish_at.is_none());
+
+        for id in [future, trashed] {
+            let post = service.repo.stored(id).unwrap();
+            assert_eq!(post.status, PostStatus::Draft, "post {}", id);
+            assert!(post.publish_at.is_some());
+        }
+        assert!(service.publish_due_posts().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn restores_from_trash_only_within_retention() {
+        let repo = with_draft().await;
+        let now = Utc::now();
+        repo.insert(Post {
+            deleted_at: Some(now - Duration::hours(47)),
+            ..post(2, AUTHOR, PostStatus::Published)
+        });
+        repo.insert(Post {
+            deleted_at: Some(now - Duration::hours(49)),
+            ..post(3, AUTHOR, PostStatus::Published)
+        });
+        repo.upsert_collaborator(2, EDITOR, PostRole::Editor, AUTHOR)
+            .await
+            .unwrap();
+        let service = PostService::new(repo).with_trash_retention(Duration::days(2));
+
+        let trash = service.list_trash(user(AUTHOR)).await.unwrap();
+        let purge_at: Vec<(i64, DateTime<Utc>)> =
+            trash.iter().map(|t| (t.post.id, t.purge_at)).collect();
+        assert_eq!(
+            purge_at,
+            vec![(2, now + Duration::hours(1)), (3, now - Duration::hours(1))]
+        );
+
+        // Пост, пролежавший в корзине дольше срока хранения, уже не восстановить
+        assert!(matches!(
+            service.restore_post(3, user(AUTHOR)).await,
+            Err(PostError::PostNotFound(_))
+        ));
+        // Живой пост восстанавливать неоткуда
+        assert!(service.restore_post(1, user(AUTHOR)).await.is_err());
+        for id in [EDITOR, STRANGER] {
+            assert!(service.restore_post(2, user(id)).await.is_err());
+        }
+        let restored = service.restore_post(2, user(AUTHOR)).await.unwrap();
+        assert!(restored.deleted_at.is_none());
+        assert_eq!(restored.version, 2);
+        assert_eq!(service.get_post(2, None).await.unwrap().id, 2);
+    }
 }
@@ -0,0 +1,242 @@
+use std::sync::Arc;
+
+use crate::application::blog_service::role_of;
+use crate::data::comment_repository::CommentRepository;
+use crate::data::post_repository::PostRepository;
+use crate::domain::collaborator::{Permission, PostRole};
+use crate::domain::comment::{
+    Comment, CommentStatus, CommentThread, MAX_COMMENT_DEPTH, NewComment, build_comment_tree,
+    validate_comment_content,
+};
+use crate::domain::error::PostError;
+use crate::domain::post::{CommentMode, Post, PostStatus};
+use crate::presentation::auth::AuthenticatedUser;
+
+#[derive(Clone)]
+pub struct CommentService<C, P>
+where
+    C: CommentRepository + 'static,
+    P: PostRepository + 'static,
+{
+    repo: Arc<C>,
+    posts: Arc<P>,
+}
+
+impl<C, P> CommentService<C, P>
+where
+    C: CommentRepository + 'static,
+    P: PostRepository + 'static,
+{
+    pub fn new(repo: Arc<C>, posts: Arc<P>) -> Self {
+        Self { repo, posts }
+    }
+
+    pub async fn add_comment(
+        &self,
+        post_id: i64,
+        parent_id: Option<i64>,
+        content: String,
+        current_user: AuthenticatedUser,
+    ) -> Result<Comment, PostError> {
+        validate_comment_content(&content)?;
+        let (post, role) = self.find_post(post_id, Some(current_user.id)).await?;
+        if post.status != PostStatus::Published {
+            return Err(PostError::InvalidState(
+                "comments are only allowed on published posts".into(),
+            ));
+        }
+        if post.comment_mode == CommentMode::Closed {
+            return Err(PostError::InvalidState(
+                "comments are closed for this post".into(),
+            ));
+        }
+        let depth = match parent_id {
+            Some(parent_id) => {
+                let parent = self.find_comment(post_id, parent_id).await?;
+                if parent.deleted || parent.status != CommentStatus::Approved {
+                    return Err(PostError::InvalidState(
+                        "can only reply to a published comment".into(),
+                    ));
+                }
+                if parent.depth + 1 >= MAX_COMMENT_DEPTH {
+                    return Err(PostError::Validation(format!(
+                        "replies cannot be nested deeper than {} levels",
+                        MAX_COMMENT_DEPTH
+                    )));
+                }
+                parent.depth + 1
+            }
+            None => 0,
+        };
+        self.repo
+            .create(NewComment {
+                post_id,
+                author_id: current_user.id,
+                parent_id,
+                depth,
+                content,
+                status: initial_status(&post, role),
+            })
+            .await
+    }
+
+    pub async fn list_comments(
+        &self,
+        post_id: i64,
+        viewer_id: Option<i64>,
+    ) -> Result<Vec<CommentThread>, PostError> {
+        self.find_post(post_id, viewer_id).await?;
+        let comments = self
+            .repo
+            .list_for_post(post_id)
+            .await?
+            .into_iter()
+            .map(|comment| comment.redact_for(viewer_id))
+            .collect();
+        Ok(build_comment_tree(comments))
+    }
+
+    // Очередь модерации видна только тем, кто может править пост
+    pub async fn list_pending(
+        &self,
+        post_id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<Vec<Comment>, PostError> {
+        self.find_moderated_post(post_id, &current_user).await?;
+        self.repo.list_pending(post_id).await
+    }
+
+    pub async fn moderate_comment(
+        &self,
+        post_id: i64,
+        id: i64,
+        decision: CommentStatus,
+        reason: Option<String>,
+        current_user: AuthenticatedUser,
+    ) -> Result<Comment, PostError> {
+        if decision == CommentStatus::Pending {
+            return Err(PostError::Validation(
+                "pending is not a moderation decision".into(),
+            ));
+        }
+        let reason = reason
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty());
+        self.find_moderated_post(post_id, &current_user).await?;
+        let comment = self.find_comment(post_id, id).await?;
+        if comment.deleted {
+            return Err(PostError::PostNotFound(format!("comment {} not found", id)));
+        }
+        if !comment.status.can_transition_to(decision) {
+            return Err(PostError::InvalidState(format!(
+                "cannot change comment status from {} to {}",
+                comment.status.as_str(),
+                decision.as_str()
+            )));
+        }
+        self.repo
+            .set_status(id, decision, reason.as_deref(), current_user.id)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("comment {} not found", id)))
+    }
+
+    pub async fn update_comment(
+        &self,
+        post_id: i64,
+        id: i64,
+        content: String,
+        current_user: AuthenticatedUser,
+    ) -> Result<Comment, PostError> {
+        validate_comment_content(&content)?;
+        let comment = self.find_own_comment(post_id, id, &current_user).await?;
+        // В премодерируемом посте правка снова отправляет комментарий на проверку
+        let (post, role) = self.find_post(post_id, Some(current_user.id)).await?;
+        let status = match comment.status {
+            CommentStatus::Approved => initial_status(&post, role),
+            status => status,
+        };
+        self.repo
+            .update_content(id, &content, status)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("comment {} not found", id)))
+    }
+
+    pub async fn delete_comment(
+        &self,
+        post_id: i64,
+        id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<(), PostError> {
+        self.find_own_comment(post_id, id, &current_user).await?;
+        self.repo
+            .mark_deleted(id)
+            .await?
+            .ok_or_else(|| PostError::PostNotFound(format!("comment {} not found", id)))?;
+        Ok(())
+    }
+
+    // Пост вместе с ролью в нём пользователя; черновики видят только автор и соавторы
+    async fn find_post(
+        &self,
+        post_id: i64,
+        viewer_id: Option<i64>,
+    ) -> Result<(Post, Option<PostRole>), PostError> {
+        let not_found = || PostError::PostNotFound(format!("post {} not found", post_id));
+        let post = self
+            .posts
+            .find_by_id(post_id)
+            .await?
+            .ok_or_else(not_found)?;
+        let role = role_of(self.posts.as_ref(), &post, viewer_id).await?;
+        if !post.is_visible_to(viewer_id) && role.is_none() {
+            return Err(not_found());
+        }
+        Ok((post, role))
+    }
+
+    async fn find_moderated_post(
+        &self,
+        post_id: i64,
+        current_user: &AuthenticatedUser,
+    ) -> Result<Post, PostError> {
+        match self.find_post(post_id, Some(current_user.id)).await? {
+            (post, Some(role)) if role.allows(Permission::Edit) => Ok(post),
+            _ => Err(PostError::Forbidden),
+        }
+    }
+
+    // Комментарий ищется в пределах поста из URL, чтобы нельзя было адресовать чужую ветку
+    async fn find_comment(&self, post_id: i64, id: i64) -> Result<Comment, PostError> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .filter(|comment| comment.post_id == post_id)
+            .ok_or_else(|| PostError::PostNotFound(format!("comment {} not found", id)))
+    }
+
+    async fn find_own_comment(
+        &self,
+        post_id: i64,
+        id: i64,
+        current_user: &AuthenticatedUser,
+    ) -> Result<Comment, PostError> {
+        let comment = self.find_comment(post_id, id).await?;
+        if comment.deleted {
+            return Err(PostError::PostNotFound(format!("comment {} not found", id)));
+        }
+        if comment.author_id != current_user.id {
+            return Err(PostError::Forbidden);
+        }
+        Ok(comment)
+    }
+}
+
+// Комментарии тех, кто сам может модерировать пост, в проверке не нуждаются
+fn initial_status(post: &Post, role: Option<PostRole>) -> CommentStatus {
+    let trusted = role.is_some_and(|role| role.allows(Permission::Edit));
+    if post.comment_mode == CommentMode::Moderated && !trusted {
+        CommentStatus::Pending
+    } else {
+        CommentStatus::Approved
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use crate::application::blog_service::{DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::data::user_repository::UserRepository;
+use crate::domain::error::AuthError;
+use crate::domain::follow::{FollowCursor, FollowPage, FollowUser};
+use crate::presentation::auth::AuthenticatedUser;
+
+#[derive(Clone)]
+pub struct FollowService<R: UserRepository + 'static> {
+    repo: Arc<R>,
+}
+
+impl<R> FollowService<R>
+where
+    R: UserRepository + 'static,
+{
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    // Повторная подписка ничего не меняет, поэтому PUT идемпотентен
+    pub async fn follow(
+        &self,
+        followee_id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<(), AuthError> {
+        if followee_id == current_user.id {
+            return Err(AuthError::Validation("cannot follow yourself".into()));
+        }
+        self.find_user(followee_id).await?;
+        if self.repo.follow(current_user.id, followee_id).await? {
+            tracing::info!(follower_id = current_user.id, followee_id, "user followed");
+        }
+        Ok(())
+    }
+
+    pub async fn unfollow(
+        &self,
+        followee_id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<(), AuthError> {
+        self.find_user(followee_id).await?;
+        if self.repo.unfollow(current_user.id, followee_id).await? {
+            tracing::info!(
+                follower_id = current_user.id,
+                followee_id,
+                "user unfollowed"
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn followers(
+        &self,
+        user_id: i64,
+        cursor: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<FollowPage, AuthError> {
+        self.find_user(user_id).await?;
+        let (after, limit) = page_params(cursor, limit)?;
+        let users = self
+            .repo
+            .find_followers(user_id, after.as_ref(), limit as i64 + 1)
+            .await?;
+        Ok(into_page(users, limit))
+    }
+
+    pub async fn following(
+        &self,
+        user_id: i64,
+        cursor: Option<&str>,
+        limit: Option<u32>,
+    ) -> Result<FollowPage, AuthError> {
+        self.find_user(user_id).await?;
+        let (after, limit) = page_params(cursor, limit)?;
+        let users = self
+            .repo
+            .find_following(user_id, after.as_ref(), limit as i64 + 1)
+            .await?;
+        Ok(into_page(users, limit))
+    }
+
+    async fn find_user(&self, id: i64) -> Result<(), AuthError> {
+        self.repo
+            .find_by_id(id)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| AuthError::UserNotFound(format!("user {}", id)))
+    }
+}
+
+fn page_params(
+    cursor: Option<&str>,
+    limit: Option<u32>,
+) -> Result<(Option<FollowCursor>, usize), AuthError> {
+    let after = cursor
+        .filter(|c| !c.is_empty())
+        .map(FollowCursor::decode)
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE) as usize;
+    Ok((after, limit))
+}
+
+// Репозиторий отдаёт на одну запись больше, чтобы понять, есть ли следующая страница
+fn into_page(mut users: Vec<FollowUser>, limit: usize) -> FollowPage {
+    let next_cursor = if users.len() > limit {
+        users.truncate(limit);
+        users.last().map(|u| FollowCursor::new(u).encode())
+    } else {
+        None
+    };
+    FollowPage { users, next_cursor }
+}
@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use tokio::sync::{Semaphore, mpsc};
+
+use crate::application::media_service::{DerivativeJob, MediaService};
+use crate::data::media_repository::MediaRepository;
+
+// Сколько загруженных картинок может ждать обработки
+pub const QUEUE_CAPACITY: usize = 64;
+
+// Фоновая задача: строит уменьшенные копии загруженных картинок,
+// одновременно не больше workers штук, чтобы не занять все ядра и память
+pub async fn run_media_processor<R>(
+    service: Arc<MediaService<R>>,
+    mut jobs: mpsc::Receiver<DerivativeJob>,
+    workers: usize,
+) where
+    R: MediaRepository + 'static,
+{
+    tracing::info!(workers, "media processor started");
+    let permits = Arc::new(Semaphore::new(workers));
+    while let Some(job) = jobs.recv().await {
+        let Ok(permit) = Arc::clone(&permits).acquire_owned().await else {
+            break;
+        };
+        let service = Arc::clone(&service);
+        tokio::spawn(async move {
+            service.process(job).await;
+            drop(permit);
+        });
+    }
+}
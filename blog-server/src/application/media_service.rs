@@ -0,0 +1,480 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use sha2::{Digest, Sha256};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+use crate::data::media_repository::MediaRepository;
+use crate::domain::error::PostError;
+use crate::domain::media::{
+    DerivativeSize, Media, MediaObject, MediaStatus, MediaVariant, NewMedia, UploadedMedia,
+    UploadedVariant, extension_for, sniff_mime, srcset, variant_key,
+};
+use crate::infrastructure::imaging::{Rendition, render_derivatives, strip_metadata};
+use crate::infrastructure::storage::{MediaStorage, StorageError};
+
+pub const DEFAULT_MAX_FILE_BYTES: i64 = 10 * 1024 * 1024;
+pub const DEFAULT_QUOTA_BYTES: i64 = 100 * 1024 * 1024;
+
+// Задание для пула обработки: построить уменьшенные копии загруженного оригинала
+pub struct DerivativeJob {
+    pub media: Media,
+    pub data: Bytes,
+}
+
+pub struct MediaService<R: MediaRepository + 'static> {
+    repo: Arc<R>,
+    storage: Arc<dyn MediaStorage>,
+    // Адрес, под которым файлы доступны снаружи, без завершающего "/"
+    base_url: String,
+    max_file_bytes: i64,
+    quota_bytes: i64,
+    sizes: Vec<DerivativeSize>,
+    // Очередь пула обработки; без неё копии строятся прямо при загрузке
+    jobs: Option<mpsc::Sender<DerivativeJob>>,
+}
+
+impl<R> MediaService<R>
+where
+    R: MediaRepository + 'static,
+{
+    pub fn new(repo: Arc<R>, storage: Arc<dyn MediaStorage>, base_url: String) -> Self {
+        Self {
+            repo,
+            storage,
+            base_url,
+            max_file_bytes: DEFAULT_MAX_FILE_BYTES,
+            quota_bytes: DEFAULT_QUOTA_BYTES,
+            sizes: Vec::new(),
+            jobs: None,
+        }
+    }
+
+    pub fn with_limits(mut self, max_file_bytes: i64, quota_bytes: i64) -> Self {
+        self.max_file_bytes = max_file_bytes;
+        self.quota_bytes = quota_bytes;
+        self
+    }
+
+    pub fn with_derivatives(
+        mut self,
+        sizes: Vec<DerivativeSize>,
+        jobs: mpsc::Sender<DerivativeJob>,
+    ) -> Self {
+        self.sizes = sizes;
+        self.jobs = Some(jobs);
+        self
+    }
+
+    pub fn max_file_bytes(&self) -> i64 {
+        self.max_file_bytes
+    }
+
+    pub async fn upload(&self, owner_id: i64, data: Bytes) -> Result<UploadedMedia, PostError> {
+        let size = data.len() as i64;
+        if size == 0 {
+            return Err(PostError::Validation("file is empty".into()));
+        }
+        if size > self.max_file_bytes {
+            return Err(PostError::PayloadTooLarge(format!(
+                "file exceeds {} bytes",
+                self.max_file_bytes
+            )));
+        }
+        let mime_type = sniff_mime(&data).ok_or_else(|| {
+            PostError::UnsupportedMediaType(
+                "only PNG, JPEG, GIF and WebP images are allowed".into(),
+            )
+        })?;
+        // Оригинал хранится и отдаётся уже без EXIF и координат съёмки
+        let data = tokio::task::spawn_blocking(move || strip_metadata(&data, mime_type))
+            .await
+            .map_err(|e| PostError::Internal(format!("media worker failed: {}", e)))?
+            .map(Bytes::from)
+            .map_err(|e| PostError::Validation(e.to_string()))?;
+        let size = data.len() as i64;
+        // Быстрая проверка до загрузки в хранилище; окончательная — при вставке в БД
+        if self.repo.used_bytes(owner_id).await? + size > self.quota_bytes {
+            return Err(quota_exceeded(self.quota_bytes));
+        }
+
+        let media = NewMedia {
+            owner_id,
+            key: format!("{}.{}", Uuid::new_v4().simple(), extension_for(mime_type)),
+            mime_type: mime_type.to_string(),
+            size,
+            checksum: hex::encode(Sha256::digest(&data)),
+        };
+        self.storage
+            .put(&media.key, data.clone(), mime_type)
+            .await
+            .map_err(storage_error)?;
+
+        let key = media.key.clone();
+        match self.repo.create_within_quota(media, self.quota_bytes).await {
+            Ok(Some(media)) => {
+                let uploaded = self.to_uploaded(media.clone(), Vec::new());
+                self.enqueue(DerivativeJob { media, data }).await;
+                Ok(uploaded)
+            }
+            result => {
+                // Файл без записи в БД никому не виден, поэтому его сразу убираем
+                if let Err(e) = self.storage.delete(&key).await {
+                    tracing::warn!("failed to remove orphaned media {}: {}", key, e);
+                }
+                Err(result
+                    .err()
+                    .unwrap_or_else(|| quota_exceeded(self.quota_bytes)))
+            }
+        }
+    }
+
+    pub async fn list(&self, owner_id: i64) -> Result<Vec<UploadedMedia>, PostError> {
+        let media = self.repo.find_by_owner(owner_id).await?;
+        let ids: Vec<i64> = media.iter().map(|m| m.id).collect();
+        let mut variants = self.repo.find_variants(&ids).await?;
+        Ok(media
+            .into_iter()
+            .map(|m| {
+                let own = variants.extract_if(.., |v| v.media_id == m.id).collect();
+                self.to_uploaded(m, own)
+            })
+            .collect())
+    }
+
+    // Чужие файлы не отличаются от несуществующих
+    pub async fn get(&self, owner_id: i64, id: i64) -> Result<UploadedMedia, PostError> {
+        let media = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .filter(|m| m.owner_id == owner_id)
+            .ok_or_else(|| PostError::PostNotFound(format!("media {}", id)))?;
+        let variants = self.repo.find_variants(&[id]).await?;
+        Ok(self.to_uploaded(media, variants))
+    }
+
+    // Метаданные и содержимое оригинала или копии для отдачи клиенту
+    pub async fn open(&self, key: &str) -> Result<(MediaObject, Bytes), PostError> {
+        let not_found = || PostError::PostNotFound(format!("media {}", key));
+        let object = self.repo.find_object(key).await?.ok_or_else(not_found)?;
+        let data = self
+            .storage
+            .get(key)
+            .await
+            .map_err(storage_error)?
+            .ok_or_else(not_found)?;
+        Ok((object, data))
+    }
+
+    // Ставит в очередь файлы, обработка которых не завершилась до остановки сервера
+    pub async fn resume_processing(&self) -> Result<usize, PostError> {
+        let pending = self.repo.find_processing().await?;
+        let count = pending.len();
+        for media in pending {
+            match self.storage.get(&media.key).await.map_err(storage_error)? {
+                Some(data) => self.enqueue(DerivativeJob { media, data }).await,
+                None => {
+                    tracing::warn!(media_id = media.id, "media content is missing from storage");
+                    self.repo.mark_failed(media.id).await?;
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    async fn enqueue(&self, job: DerivativeJob) {
+        match &self.jobs {
+            // Очередь ограничена: при её заполнении загрузка ждёт, а не копит задания в памяти
+            Some(jobs) => {
+                if let Err(e) = jobs.send(job).await {
+                    tracing::error!(media_id = e.0.media.id, "media processing queue is closed");
+                }
+            }
+            None => self.process(job).await,
+        }
+    }
+
+    // Строит копии вне потоков actix; ошибка оставляет оригинал доступным со статусом failed
+    pub async fn process(&self, job: DerivativeJob) {
+        let media_id = job.media.id;
+        if let Err(e) = self.render_and_store(job).await {
+            tracing::error!(media_id, "failed to process media: {}", e);
+            if let Err(e) = self.repo.mark_failed(media_id).await {
+                tracing::error!(media_id, "failed to mark media as failed: {}", e);
+            }
+        }
+    }
+
+    async fn render_and_store(&self, job: DerivativeJob) -> Result<(), PostError> {
+        let DerivativeJob { media, data } = job;
+        let mime_type = media.mime_type.clone();
+        let sizes = self.sizes.clone();
+        let rendered =
+            tokio::task::spawn_blocking(move || render_derivatives(&data, &mime_type, &sizes))
+                .await
+                .map_err(|e| PostError::Internal(format!("media worker failed: {}", e)))?
+                .map_err(|e| PostError::Internal(e.to_string()))?;
+
+        let mut variants = Vec::with_capacity(rendered.renditions.len());
+        let stored = self
+            .store_variants(&media, rendered.renditions, &mut variants)
+            .await;
+        let completed = match stored {
+            Ok(()) => {
+                self.repo
+                    .complete_processing(
+                        media.id,
+                        rendered.width as i32,
+                        rendered.height as i32,
+                        &variants,
+                    )
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        match completed {
+            Ok(stale) => {
+                self.remove_blobs(&stale).await;
+                Ok(())
+            }
+            Err(e) => {
+                // Копии без записей в БД никому не видны, поэтому уже загруженные убираем
+                let keys: Vec<String> = variants.into_iter().map(|variant| variant.key).collect();
+                self.remove_blobs(&keys).await;
+                Err(e)
+            }
+        }
+    }
+
+    // Загружает копии по одной; в variants попадают только уже сохранённые
+    async fn store_variants(
+        &self,
+        media: &Media,
+        renditions: Vec<Rendition>,
+        variants: &mut Vec<MediaVariant>,
+    ) -> Result<(), PostError> {
+        for rendition in renditions {
+            let variant = MediaVariant {
+                media_id: media.id,
+                key: variant_key(&media.key, &rendition.name, rendition.mime_type),
+                name: rendition.name,
+                mime_type: rendition.mime_type.to_string(),
+                width: rendition.width as i32,
+                height: rendition.height as i32,
+                size: rendition.data.len() as i64,
+                checksum: hex::encode(Sha256::digest(&rendition.data)),
+            };
+            self.storage
+                .put(
+                    &variant.key,
+                    Bytes::from(rendition.data),
+                    rendition.mime_type,
+                )
+                .await
+                .map_err(storage_error)?;
+            variants.push(variant);
+        }
+        Ok(())
+    }
+
+    async fn remove_blobs(&self, keys: &[String]) {
+        for key in keys {
+            if let Err(e) = self.storage.delete(key).await {
+                tracing::warn!("failed to remove media variant {}: {}", key, e);
+            }
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/{}", self.base_url, key)
+    }
+
+    fn to_uploaded(&self, media: Media, variants: Vec<MediaVariant>) -> UploadedMedia {
+        let url = self.url(&media.key);
+        // Пока копии не готовы, srcset не отдаётся: адреса из него ещё не открываются
+        let (srcset, srcset_webp) = match (media.status, media.width) {
+            (MediaStatus::Ready, Some(width)) => {
+                let candidates = |mime_type: &str| {
+                    variants
+                        .iter()
+                        .filter(|v| v.mime_type == mime_type)
+                        .map(|v| (self.url(&v.key), v.width))
+                        .chain((media.mime_type == mime_type).then(|| (url.clone(), width)))
+                        .collect::<Vec<_>>()
+                };
+                (
+                    srcset(candidates(&media.mime_type)),
+                    srcset(candidates("image/webp")),
+                )
+            }
+            _ => (None, None),
+        };
+        UploadedMedia {
+            markdown: format!("![]({})", url),
+            url,
+            variants: variants
+                .into_iter()
+                .map(|variant| UploadedVariant {
+                    url: self.url(&variant.key),
+                    variant,
+                })
+                .collect(),
+            srcset,
+            srcset_webp,
+            media,
+        }
+    }
+}
+
+fn quota_exceeded(quota: i64) -> PostError {
+    PostError::PayloadTooLarge(format!("upload quota of {} bytes exceeded", quota))
+}
+
+fn storage_error(e: StorageError) -> PostError {
+    tracing::error!("media storage error: {}", e);
+    PostError::Internal(format!("storage error: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::memory::MemoryMediaRepository;
+    use async_trait::async_trait;
+    use image::{DynamicImage, ImageFormat, RgbImage};
+    use std::collections::HashMap;
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    // Хранилище в памяти, отказывающее в записи ключей с подстрокой fail_on
+    #[derive(Default)]
+    struct MemoryStorage {
+        objects: Mutex<HashMap<String, Bytes>>,
+        fail_on: Option<&'static str>,
+    }
+
+    impl MemoryStorage {
+        fn keys(&self) -> Vec<String> {
+            let mut keys: Vec<String> = self.objects.lock().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        }
+    }
+
+    #[async_trait]
+    impl MediaStorage for MemoryStorage {
+        async fn put(&self, key: &str, data: Bytes, _: &str) -> Result<(), StorageError> {
+            if self.fail_on.is_some_and(|part| key.contains(part)) {
+                return Err(StorageError::Http(format!("PUT {} returned 500", key)));
+            }
+            self.objects.lock().unwrap().insert(key.to_string(), data);
+            Ok(())
+        }
+        async fn get(&self, key: &str) -> Result<Option<Bytes>, StorageError> {
+            Ok(self.objects.lock().unwrap().get(key).cloned())
+        }
+        async fn delete(&self, key: &str) -> Result<(), StorageError> {
+            self.objects.lock().unwrap().remove(key);
+            Ok(())
+        }
+    }
+
+    fn service(
+        repo: Arc<MemoryMediaRepository>,
+        storage: Arc<MemoryStorage>,
+    ) -> MediaService<MemoryMediaRepository> {
+        let sizes = vec![
+            DerivativeSize {
+                name: "small".into(),
+                width: 40,
+            },
+            DerivativeSize {
+                name: "large".into(),
+                width: 400,
+            },
+        ];
+        let mut service = MediaService::new(repo, storage, "/media".into());
+        service.sizes = sizes;
+        service
+    }
+
+    // Загруженный PNG 100x50, ожидающий обработки
+    async fn job(repo: &MemoryMediaRepository) -> DerivativeJob {
+        let mut png = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(RgbImage::new(100, 50))
+            .write_to(&mut png, ImageFormat::Png)
+            .unwrap();
+        let data = Bytes::from(png.into_inner());
+        let media = repo
+            .create_within_quota(
+                NewMedia {
+                    owner_id: 1,
+                    key: "abc.png".into(),
+                    mime_type: "image/png".into(),
+                    size: data.len() as i64,
+                    checksum: String::new(),
+                },
+                DEFAULT_QUOTA_BYTES,
+            )
+            .await
+            .unwrap()
+            .unwrap();
+        DerivativeJob { media, data }
+    }
+
+    async fn status(repo: &MemoryMediaRepository) -> MediaStatus {
+        repo.find_by_id(1).await.unwrap().unwrap().status
+    }
+
+    #[tokio::test]
+    async fn failed_processing_removes_stored_variants() {
+        let repo = Arc::new(MemoryMediaRepository::default());
+        let storage = Arc::new(MemoryStorage {
+            fail_on: Some("large"),
+            ..Default::default()
+        });
+        let job = job(&repo).await;
+        service(repo.clone(), storage.clone()).process(job).await;
+
+        assert_eq!(status(&repo).await, MediaStatus::Failed);
+        assert!(storage.keys().is_empty());
+    }
+
+    #[tokio::test]
+    async fn reprocessing_removes_outdated_variants() {
+        let repo = Arc::new(MemoryMediaRepository::default());
+        let storage = Arc::new(MemoryStorage::default());
+        let service = service(repo.clone(), storage.clone());
+        let job = job(&repo).await;
+        let media = job.media.clone();
+        let data = job.data.clone();
+        service.process(job).await;
+
+        // Копия размера, которого больше нет в настройках
+        let mut variants = repo.find_variants(&[media.id]).await.unwrap();
+        variants.push(MediaVariant {
+            key: "abc-medium.webp".into(),
+            ..variants[0].clone()
+        });
+        repo.complete_processing(media.id, 100, 50, &variants)
+            .await
+            .unwrap();
+        storage
+            .put("abc-medium.webp", Bytes::from_static(b"old"), "image/webp")
+            .await
+            .unwrap();
+
+        service.process(DerivativeJob { media, data }).await;
+        assert_eq!(status(&repo).await, MediaStatus::Ready);
+        assert_eq!(
+            storage.keys(),
+            vec![
+                "abc-large.png",
+                "abc-large.webp",
+                "abc-small.png",
+                "abc-small.webp"
+            ]
+        );
+    }
+}
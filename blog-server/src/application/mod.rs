@@ -1,2 +1,12 @@
 pub mod auth_service;
-pub mod blog_service;
\ No newline at end of file
+pub mod blog_service;
+pub mod publish_scheduler;
+pub mod tag_service;
+pub mod comment_service;
+pub mod follow_service;
+pub mod trash_purger;
+pub mod media_service;
+pub mod media_processor;
+pub mod series_service;
+pub mod analytics_service;
+pub mod view_flusher;
\ No newline at end of file
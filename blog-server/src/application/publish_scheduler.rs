@@ -0,0 +1,27 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::application::blog_service::PostService;
+use crate::data::post_repository::PostRepository;
+
+// Фоновая задача: периодически публикует черновики с наступившим publish_at.
+// Безопасна при нескольких инстансах — строки блокируются в репозитории.
+pub async fn run_publish_scheduler<R>(service: Arc<PostService<R>>, interval: Duration)
+where
+    R: PostRepository + 'static,
+{
+    tracing::info!(
+        interval_secs = interval.as_secs(),
+        "publish scheduler started"
+    );
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match service.publish_due_posts().await {
+            Ok(posts) if posts.is_empty() => {}
+            Ok(posts) => tracing::info!(count = posts.len(), "scheduled posts published"),
+            Err(e) => tracing::error!("publish scheduler failed: {}", e),
+        }
+    }
+}
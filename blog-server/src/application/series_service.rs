@@ -0,0 +1,188 @@
+use std::sync::Arc;
+
+use crate::data::series_repository::SeriesRepository;
+use crate::domain::error::PostError;
+use crate::domain::series::{NewSeries, Series, SeriesContents, validate_series_posts};
+use crate::presentation::auth::AuthenticatedUser;
+
+#[derive(Clone)]
+pub struct SeriesService<R: SeriesRepository + 'static> {
+    repo: Arc<R>,
+}
+
+impl<R> SeriesService<R>
+where
+    R: SeriesRepository + 'static,
+{
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn create_series(
+        &self,
+        title: String,
+        description: String,
+        current_user: AuthenticatedUser,
+    ) -> Result<Series, PostError> {
+        let series = NewSeries {
+            owner_id: current_user.id,
+            title,
+            description,
+        };
+        series.validate()?;
+        self.repo.create(series).await
+    }
+
+    // Черновики и архивные части в оглавлении видит только владелец серии
+    pub async fn get_series(
+        &self,
+        id: i64,
+        viewer_id: Option<i64>,
+    ) -> Result<SeriesContents, PostError> {
+        let series = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| series_not_found(id))?;
+        let posts = self
+            .repo
+            .entries(id, viewer_id == Some(series.owner_id))
+            .await?;
+        Ok(SeriesContents { series, posts })
+    }
+
+    pub async fn update_series(
+        &self,
+        id: i64,
+        title: String,
+        description: String,
+        current_user: AuthenticatedUser,
+    ) -> Result<Series, PostError> {
+        let update = NewSeries {
+            owner_id: current_user.id,
+            title,
+            description,
+        };
+        update.validate()?;
+        self.owned_series(id, &current_user).await?;
+        self.repo
+            .update(id, &update.title, &update.description)
+            .await?
+            .ok_or_else(|| series_not_found(id))
+    }
+
+    pub async fn delete_series(
+        &self,
+        id: i64,
+        current_user: AuthenticatedUser,
+    ) -> Result<(), PostError> {
+        self.owned_series(id, &current_user).await?;
+        if !self.repo.delete(id).await? {
+            return Err(series_not_found(id));
+        }
+        Ok(())
+    }
+
+    // Задаёт состав и порядок частей; в серию входят только собственные посты владельца
+    pub async fn set_series_posts(
+        &self,
+        id: i64,
+        post_ids: Vec<i64>,
+        current_user: AuthenticatedUser,
+    ) -> Result<SeriesContents, PostError> {
+        validate_series_posts(&post_ids)?;
+        let series = self.owned_series(id, &current_user).await?;
+        self.repo.set_posts(id, series.owner_id, &post_ids).await?;
+        let posts = self.repo.entries(id, true).await?;
+        Ok(SeriesContents { series, posts })
+    }
+
+    async fn owned_series(
+        &self,
+        id: i64,
+        current_user: &AuthenticatedUser,
+    ) -> Result<Series, PostError> {
+        let series = self
+            .repo
+            .find_by_id(id)
+            .await?
+            .ok_or_else(|| series_not_found(id))?;
+        if series.owner_id != current_user.id {
+            return Err(PostError::Forbidden);
+        }
+        Ok(series)
+    }
+}
+
+fn series_not_found(id: i64) -> PostError {
+    PostError::PostNotFound(format!("series {} not found", id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::memory::{MemoryPostRepository, MemorySeriesRepository, post};
+    use crate::domain::post::PostStatus;
+
+    fn user(id: i64) -> AuthenticatedUser {
+        AuthenticatedUser {
+            id,
+            email: format!("user{}@example.com", id),
+        }
+    }
+
+    #[tokio::test]
+    async fn only_owner_sets_series_posts() {
+        let posts = Arc::new(MemoryPostRepository::default());
+        posts.insert(post(2, 1, PostStatus::Published));
+        posts.insert(post(3, 1, PostStatus::Draft));
+        posts.insert(post(4, 2, PostStatus::Published));
+        let service = SeriesService::new(Arc::new(MemorySeriesRepository::new(posts)));
+        let series = service
+            .create_series("Series".into(), String::new(), user(1))
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            service
+                .set_series_posts(series.id, vec![3, 2], user(2))
+                .await,
+            Err(PostError::Forbidden)
+        ));
+        assert!(matches!(
+            service.set_series_posts(5, vec![3, 2], user(1)).await,
+            Err(PostError::PostNotFound(_))
+        ));
+        assert!(matches!(
+            service
+                .set_series_posts(series.id, vec![3, 3], user(1))
+                .await,
+            Err(PostError::Validation(_))
+        ));
+        // Посты проверяются на принадлежность владельцу серии
+        assert!(matches!(
+            service
+                .set_series_posts(series.id, vec![2, 4], user(1))
+                .await,
+            Err(PostError::Validation(_))
+        ));
+        assert!(
+            service
+                .get_series(series.id, Some(1))
+                .await
+                .unwrap()
+                .posts
+                .is_empty()
+        );
+
+        let contents = service
+            .set_series_posts(series.id, vec![3, 2], user(1))
+            .await
+            .unwrap();
+        let parts: Vec<(i32, i64)> = contents.posts.iter().map(|p| (p.position, p.id)).collect();
+        assert_eq!(parts, vec![(1, 3), (2, 2)]);
+        // Черновик в оглавлении видит только владелец
+        let public = service.get_series(series.id, None).await.unwrap();
+        assert_eq!(public.posts.len(), 1);
+    }
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use crate::data::tag_repository::TagRepository;
+use crate::domain::{error::PostError, tag::TagCount};
+
+#[derive(Clone)]
+pub struct TagService<R: TagRepository + 'static> {
+    repo: Arc<R>,
+}
+
+impl<R> TagService<R>
+where
+    R: TagRepository + 'static,
+{
+    pub fn new(repo: Arc<R>) -> Self {
+        Self { repo }
+    }
+
+    pub async fn list_tags(&self) -> Result<Vec<TagCount>, PostError> {
+        self.repo.list_with_counts().await
+    }
+}
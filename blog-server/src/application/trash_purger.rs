@@ -0,0 +1,70 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::application::blog_service::PostService;
+use crate::data::post_repository::PostRepository;
+
+// Фоновая задача: окончательно удаляет посты, у которых истёк срок хранения в корзине
+pub async fn run_trash_purger<R>(service: Arc<PostService<R>>, interval: Duration)
+where
+    R: PostRepository + 'static,
+{
+    tracing::info!(interval_secs = interval.as_secs(), "trash purger started");
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match service.purge_expired_posts().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!(count, "expired posts purged from trash"),
+            Err(e) => tracing::error!("trash purger failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::application::blog_service::PURGE_BATCH_SIZE;
+    use crate::data::memory::{MemoryPostRepository, post};
+    use crate::domain::post::{Post, PostStatus};
+
+    #[tokio::test]
+    async fn purges_only_expired_posts() {
+        let repo = Arc::new(MemoryPostRepository::default());
+        let now = Utc::now();
+        // Больше одной пачки, чтобы сервис запросил следующую
+        let expired = PURGE_BATCH_SIZE + 1;
+        for id in 1..=expired {
+            repo.insert(Post {
+                deleted_at: Some(now - chrono::Duration::days(31)),
+                ..post(id, 1, PostStatus::Published)
+            });
+        }
+        let fresh = expired + 1;
+        repo.insert(Post {
+            deleted_at: Some(now - chrono::Duration::days(29)),
+            ..post(fresh, 1, PostStatus::Published)
+        });
+        let live = expired + 2;
+        repo.insert(post(live, 1, PostStatus::Published));
+
+        let service = Arc::new(PostService::new(repo.clone()));
+        let purger = tokio::spawn(run_trash_purger(service, Duration::from_secs(3600)));
+        // Первый тик срабатывает сразу после запуска
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while repo.stored(expired).is_some() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        purger.abort();
+
+        assert!((1..=expired).all(|id| repo.stored(id).is_none()));
+        assert!(repo.stored(fresh).is_some());
+        assert!(repo.stored(live).is_some());
+    }
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::application::analytics_service::AnalyticsService;
+use crate::data::analytics_repository::AnalyticsRepository;
+use crate::data::post_repository::PostRepository;
+
+// Хэши хранятся двое суток, так что чистить их чаще раза в час незачем
+pub const VISITOR_PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+// Фоновая задача: переносит накопленные просмотры в базу и чистит устаревшие хэши посетителей
+pub async fn run_view_flusher<A, P>(service: Arc<AnalyticsService<A, P>>, interval: Duration)
+where
+    A: AnalyticsRepository + 'static,
+    P: PostRepository + 'static,
+{
+    tracing::info!(interval_secs = interval.as_secs(), "view flusher started");
+    let mut flush_ticker = tokio::time::interval(interval);
+    flush_ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut prune_ticker = tokio::time::interval(VISITOR_PRUNE_INTERVAL);
+    prune_ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = flush_ticker.tick() => match service.flush_views().await {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!(count, "post views flushed"),
+                Err(e) => tracing::error!("view flusher failed: {}", e),
+            },
+            _ = prune_ticker.tick() => match service.prune_visitors().await {
+                Ok(0) => {}
+                Ok(count) => tracing::info!(count, "expired view visitors pruned"),
+                Err(e) => tracing::error!("failed to prune view visitors: {}", e),
+            },
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::{PgPool, Row};
+use tracing;
+
+use crate::domain::analytics::{DailyViews, ViewKey, ViewTotals};
+use crate::domain::error::PostError;
+
+#[async_trait]
+pub trait AnalyticsRepository: Send + Sync {
+    // Добавляет просмотры к дневным счётчикам; уже учтённый за день посетитель
+    // увеличивает только число просмотров
+    async fn record_views(&self, views: &[(ViewKey, i64)]) -> Result<(), PostError>;
+    // Хэши посетителей нужны только для дедупликации в пределах суток
+    async fn prune_visitors(&self, before: NaiveDate) -> Result<u64, PostError>;
+    async fn daily_views(
+        &self,
+        post_id: i64,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyViews>, PostError>;
+    async fn total_views(&self, post_id: i64) -> Result<ViewTotals, PostError>;
+}
+
+#[derive(Clone)]
+pub struct PostgresAnalyticsRepository {
+    pool: PgPool,
+}
+
+impl PostgresAnalyticsRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AnalyticsRepository for PostgresAnalyticsRepository {
+    async fn record_views(&self, views: &[(ViewKey, i64)]) -> Result<(), PostError> {
+        let post_ids: Vec<i64> = views.iter().map(|(key, _)| key.post_id).collect();
+        let days: Vec<NaiveDate> = views.iter().map(|(key, _)| key.day).collect();
+        let hashes: Vec<&str> = views
+            .iter()
+            .map(|(key, _)| key.visitor_hash.as_str())
+            .collect();
+        let counts: Vec<i64> = views.iter().map(|(_, count)| *count).collect();
+        // Один запрос на весь буфер: новые посетители определяются по вставленным строкам,
+        // а посты, удалённые до записи, пропускаются
+        sqlx::query(
+            r#"
+            WITH batch AS (
+                SELECT b.*
+                FROM unnest($1::BIGINT[], $2::DATE[], $3::TEXT[], $4::BIGINT[])
+                    AS b(post_id, day, visitor_hash, views)
+                JOIN posts p ON p.id = b.post_id
+            ),
+            new_visitors AS (
+                INSERT INTO post_view_visitors (post_id, day, visitor_hash)
+                SELECT post_id, day, visitor_hash FROM batch
+                ON CONFLICT DO NOTHING
+                RETURNING post_id, day
+            ),
+            totals AS (
+                SELECT post_id, day, SUM(views)::BIGINT AS views,
+                       (SELECT COUNT(*) FROM new_visitors n
+                        WHERE n.post_id = b.post_id AND n.day = b.day) AS visitors
+                FROM batch b
+                GROUP BY post_id, day
+            )
+            INSERT INTO post_view_stats (post_id, day, views, visitors)
+            SELECT post_id, day, views, visitors FROM totals
+            ON CONFLICT (post_id, day) DO UPDATE
+            SET views = post_view_stats.views + EXCLUDED.views,
+                visitors = post_view_stats.visitors + EXCLUDED.visitors
+            "#,
+        )
+        .bind(&post_ids)
+        .bind(&days)
+        .bind(&hashes)
+        .bind(&counts)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to record post views: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(())
+    }
+
+    async fn prune_visitors(&self, before: NaiveDate) -> Result<u64, PostError> {
+        let result = sqlx::query("DELETE FROM post_view_visitors WHERE day < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to prune view visitors: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        Ok(result.rows_affected())
+    }
+
+    async fn daily_views(
+        &self,
+        post_id: i64,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyViews>, PostError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT day, views, visitors
+            FROM post_view_stats
+            WHERE post_id = $1 AND day BETWEEN $2 AND $3
+            ORDER BY day
+            "#,
+        )
+        .bind(post_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to load daily views: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows
+            .iter()
+            .map(|row| DailyViews {
+                date: row.get("day"),
+                views: row.get("views"),
+                visitors: row.get("visitors"),
+            })
+            .collect())
+    }
+
+    async fn total_views(&self, post_id: i64) -> Result<ViewTotals, PostError> {
+        let (views, visitors): (i64, i64) = sqlx::query_as(
+            r#"
+            SELECT COALESCE(SUM(views), 0)::BIGINT, COALESCE(SUM(visitors), 0)::BIGINT
+            FROM post_view_stats
+            WHERE post_id = $1
+            "#,
+        )
+        .bind(post_id)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to load total views: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(ViewTotals { views, visitors })
+    }
+}
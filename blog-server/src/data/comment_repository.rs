@@ -0,0 +1,225 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing;
+
+use crate::domain::comment::{Comment, CommentStatus, NewComment};
+use crate::domain::error::PostError;
+
+#[async_trait]
+pub trait CommentRepository: Send + Sync {
+    async fn create(&self, comment: NewComment) -> Result<Comment, PostError>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<Comment>, PostError>;
+    async fn list_for_post(&self, post_id: i64) -> Result<Vec<Comment>, PostError>;
+    async fn list_pending(&self, post_id: i64) -> Result<Vec<Comment>, PostError>;
+    async fn update_content(
+        &self,
+        id: i64,
+        content: &str,
+        status: CommentStatus,
+    ) -> Result<Option<Comment>, PostError>;
+    async fn mark_deleted(&self, id: i64) -> Result<Option<Comment>, PostError>;
+    async fn set_status(
+        &self,
+        id: i64,
+        status: CommentStatus,
+        reason: Option<&str>,
+        moderator_id: i64,
+    ) -> Result<Option<Comment>, PostError>;
+}
+
+const COMMENT_COLUMNS: &str = "id, post_id, author_id, parent_id, depth, content, status, \
+     moderation_reason, created_at, edited_at, deleted_at";
+
+#[derive(Clone)]
+pub struct PostgresCommentRepository {
+    pool: PgPool,
+}
+
+impl PostgresCommentRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CommentRepository for PostgresCommentRepository {
+    async fn create(&self, comment: NewComment) -> Result<Comment, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO comments (post_id, author_id, parent_id, depth, content, status)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {COMMENT_COLUMNS}
+            "#
+        ))
+        .bind(comment.post_id)
+        .bind(comment.author_id)
+        .bind(comment.parent_id)
+        .bind(comment.depth)
+        .bind(&comment.content)
+        .bind(comment.status.as_str())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to create comment: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let comment = row_to_comment(&row);
+        tracing::info!(comment_id = %comment.id, post_id = %comment.post_id, "comment created");
+        Ok(comment)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Comment>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {COMMENT_COLUMNS}
+            FROM comments
+            WHERE id = $1
+            "#
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to find comment: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(row.as_ref().map(row_to_comment))
+    }
+
+    async fn list_for_post(&self, post_id: i64) -> Result<Vec<Comment>, PostError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {COMMENT_COLUMNS}
+            FROM comments
+            WHERE post_id = $1
+            ORDER BY created_at, id
+            "#
+        ))
+        .bind(post_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to list comments: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows.iter().map(row_to_comment).collect())
+    }
+
+    async fn list_pending(&self, post_id: i64) -> Result<Vec<Comment>, PostError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {COMMENT_COLUMNS}
+            FROM comments
+            WHERE post_id = $1 AND status = 'pending' AND deleted_at IS NULL
+            ORDER BY created_at, id
+            "#
+        ))
+        .bind(post_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to list pending comments: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows.iter().map(row_to_comment).collect())
+    }
+
+    async fn update_content(
+        &self,
+        id: i64,
+        content: &str,
+        status: CommentStatus,
+    ) -> Result<Option<Comment>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE comments
+            SET content = $1, status = $2, edited_at = now()
+            WHERE id = $3 AND deleted_at IS NULL
+            RETURNING {COMMENT_COLUMNS}
+            "#
+        ))
+        .bind(content)
+        .bind(status.as_str())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to update comment: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(row.as_ref().map(row_to_comment))
+    }
+
+    async fn mark_deleted(&self, id: i64) -> Result<Option<Comment>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE comments
+            SET content = '', deleted_at = now()
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING {COMMENT_COLUMNS}
+            "#
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to delete comment: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        if row.is_some() {
+            tracing::info!(comment_id = %id, "comment deleted");
+        }
+        Ok(row.as_ref().map(row_to_comment))
+    }
+
+    async fn set_status(
+        &self,
+        id: i64,
+        status: CommentStatus,
+        reason: Option<&str>,
+        moderator_id: i64,
+    ) -> Result<Option<Comment>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE comments
+            SET status = $1, moderation_reason = $2, moderated_by = $3, moderated_at = now()
+            WHERE id = $4 AND deleted_at IS NULL
+            RETURNING {COMMENT_COLUMNS}
+            "#
+        ))
+        .bind(status.as_str())
+        .bind(reason)
+        .bind(moderator_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to moderate comment: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        if row.is_some() {
+            tracing::info!(comment_id = %id, status = %status.as_str(), "comment moderated");
+        }
+        Ok(row.as_ref().map(row_to_comment))
+    }
+}
+
+fn row_to_comment(row: &PgRow) -> Comment {
+    let deleted_at: Option<chrono::DateTime<chrono::Utc>> = row.get("deleted_at");
+    let status: String = row.get("status");
+    Comment {
+        id: row.get("id"),
+        post_id: row.get("post_id"),
+        author_id: row.get("author_id"),
+        parent_id: row.get("parent_id"),
+        depth: row.get("depth"),
+        content: row.get("content"),
+        deleted: deleted_at.is_some(),
+        // CHECK-ограничение в таблице не пропускает неизвестные значения
+        status: CommentStatus::parse(&status).unwrap_or(CommentStatus::Pending),
+        moderation_reason: row.get("moderation_reason"),
+        created_at: row.get("created_at"),
+        edited_at: row.get("edited_at"),
+    }
+}
@@ -0,0 +1,291 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing;
+
+use crate::domain::error::PostError;
+use crate::domain::media::{Media, MediaObject, MediaStatus, MediaVariant, NewMedia};
+
+#[async_trait]
+pub trait MediaRepository: Send + Sync {
+    // None, если с этим файлом владелец превысит quota байт
+    async fn create_within_quota(
+        &self,
+        media: NewMedia,
+        quota: i64,
+    ) -> Result<Option<Media>, PostError>;
+    async fn used_bytes(&self, owner_id: i64) -> Result<i64, PostError>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<Media>, PostError>;
+    // Оригинал или уменьшенная копия с этим ключом
+    async fn find_object(&self, key: &str) -> Result<Option<MediaObject>, PostError>;
+    async fn find_by_owner(&self, owner_id: i64) -> Result<Vec<Media>, PostError>;
+    async fn find_variants(&self, media_ids: &[i64]) -> Result<Vec<MediaVariant>, PostError>;
+    // Файлы, копии которых ещё не построены, в том числе прерванные перезапуском
+    async fn find_processing(&self) -> Result<Vec<Media>, PostError>;
+    // Заменяет копии файла и переводит его в ready; возвращает ключи прежних копий,
+    // которых нет среди новых, чтобы вызывающий удалил их из хранилища
+    async fn complete_processing(
+        &self,
+        media_id: i64,
+        width: i32,
+        height: i32,
+        variants: &[MediaVariant],
+    ) -> Result<Vec<String>, PostError>;
+    async fn mark_failed(&self, media_id: i64) -> Result<(), PostError>;
+}
+
+const MEDIA_COLUMNS: &str =
+    "id, owner_id, key, mime_type, size, checksum, width, height, status, created_at";
+const VARIANT_COLUMNS: &str = "media_id, name, mime_type, key, width, height, size, checksum";
+
+#[derive(Clone)]
+pub struct PostgresMediaRepository {
+    pool: PgPool,
+}
+
+impl PostgresMediaRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl MediaRepository for PostgresMediaRepository {
+    async fn create_within_quota(
+        &self,
+        media: NewMedia,
+        quota: i64,
+    ) -> Result<Option<Media>, PostError> {
+        // Строка пользователя блокируется, чтобы параллельные загрузки не обошли квоту
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            tracing::error!("failed to start transaction: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        sqlx::query("SELECT 1 FROM users WHERE id = $1 FOR UPDATE")
+            .bind(media.owner_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to lock media owner: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO media (owner_id, key, mime_type, size, checksum)
+            SELECT $1, $2, $3, $4, $5
+            WHERE (SELECT COALESCE(SUM(size), 0) FROM media WHERE owner_id = $1) + $4 <= $6
+            RETURNING {MEDIA_COLUMNS}
+            "#
+        ))
+        .bind(media.owner_id)
+        .bind(&media.key)
+        .bind(&media.mime_type)
+        .bind(media.size)
+        .bind(&media.checksum)
+        .bind(quota)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to create media: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        tx.commit().await.map_err(|e| {
+            tracing::error!("failed to commit media creation: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let media = row.map(|row| row_to_media(&row));
+        if let Some(media) = &media {
+            tracing::info!(media_id = %media.id, owner_id = %media.owner_id, size = media.size, "media uploaded");
+        }
+        Ok(media)
+    }
+
+    async fn used_bytes(&self, owner_id: i64) -> Result<i64, PostError> {
+        sqlx::query_scalar("SELECT COALESCE(SUM(size), 0)::BIGINT FROM media WHERE owner_id = $1")
+            .bind(owner_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to count media usage: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Media>, PostError> {
+        let row = sqlx::query(&format!("SELECT {MEDIA_COLUMNS} FROM media WHERE id = $1"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to find media {}: {}", id, e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        Ok(row.map(|row| row_to_media(&row)))
+    }
+
+    async fn find_object(&self, key: &str) -> Result<Option<MediaObject>, PostError> {
+        let row = sqlx::query(
+            r#"
+            SELECT mime_type, checksum FROM media WHERE key = $1
+            UNION ALL
+            SELECT mime_type, checksum FROM media_variants WHERE key = $1
+            LIMIT 1
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to find media {}: {}", key, e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(row.map(|row| MediaObject {
+            mime_type: row.get("mime_type"),
+            checksum: row.get("checksum"),
+        }))
+    }
+
+    async fn find_by_owner(&self, owner_id: i64) -> Result<Vec<Media>, PostError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {MEDIA_COLUMNS} FROM media WHERE owner_id = $1 ORDER BY created_at DESC, id DESC"
+        ))
+        .bind(owner_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to list media: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows.iter().map(row_to_media).collect())
+    }
+
+    async fn find_variants(&self, media_ids: &[i64]) -> Result<Vec<MediaVariant>, PostError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {VARIANT_COLUMNS} FROM media_variants WHERE media_id = ANY($1) ORDER BY media_id, width, mime_type"
+        ))
+        .bind(media_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to list media variants: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows.iter().map(row_to_variant).collect())
+    }
+
+    async fn find_processing(&self) -> Result<Vec<Media>, PostError> {
+        let rows = sqlx::query(&format!(
+            "SELECT {MEDIA_COLUMNS} FROM media WHERE status = $1 ORDER BY id"
+        ))
+        .bind(MediaStatus::Processing.as_str())
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to list unprocessed media: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows.iter().map(row_to_media).collect())
+    }
+
+    async fn complete_processing(
+        &self,
+        media_id: i64,
+        width: i32,
+        height: i32,
+        variants: &[MediaVariant],
+    ) -> Result<Vec<String>, PostError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            tracing::error!("failed to start transaction: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let old_keys: Vec<String> =
+            sqlx::query_scalar("DELETE FROM media_variants WHERE media_id = $1 RETURNING key")
+                .bind(media_id)
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| {
+                    tracing::error!("failed to clear media variants: {}", e);
+                    PostError::Internal(format!("database error: {}", e))
+                })?;
+        for variant in variants {
+            sqlx::query(&format!(
+                "INSERT INTO media_variants ({VARIANT_COLUMNS}) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"
+            ))
+            .bind(media_id)
+            .bind(&variant.name)
+            .bind(&variant.mime_type)
+            .bind(&variant.key)
+            .bind(variant.width)
+            .bind(variant.height)
+            .bind(variant.size)
+            .bind(&variant.checksum)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to create media variant: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        }
+        sqlx::query("UPDATE media SET width = $2, height = $3, status = $4 WHERE id = $1")
+            .bind(media_id)
+            .bind(width)
+            .bind(height)
+            .bind(MediaStatus::Ready.as_str())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to complete media processing: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        tx.commit().await.map_err(|e| {
+            tracing::error!("failed to commit media processing: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        tracing::info!(media_id, variants = variants.len(), "media processed");
+        Ok(old_keys
+            .into_iter()
+            .filter(|key| !variants.iter().any(|variant| &variant.key == key))
+            .collect())
+    }
+
+    async fn mark_failed(&self, media_id: i64) -> Result<(), PostError> {
+        sqlx::query("UPDATE media SET status = $2 WHERE id = $1")
+            .bind(media_id)
+            .bind(MediaStatus::Failed.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to mark media {} as failed: {}", media_id, e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        Ok(())
+    }
+}
+
+fn row_to_media(row: &PgRow) -> Media {
+    Media {
+        id: row.get("id"),
+        owner_id: row.get("owner_id"),
+        key: row.get("key"),
+        mime_type: row.get("mime_type"),
+        size: row.get("size"),
+        checksum: row.get("checksum"),
+        width: row.get("width"),
+        height: row.get("height"),
+        status: MediaStatus::parse(row.get("status")).unwrap_or(MediaStatus::Failed),
+        created_at: row.get("created_at"),
+    }
+}
+
+fn row_to_variant(row: &PgRow) -> MediaVariant {
+    MediaVariant {
+        media_id: row.get("media_id"),
+        name: row.get("name"),
+        mime_type: row.get("mime_type"),
+        key: row.get("key"),
+        width: row.get("width"),
+        height: row.get("height"),
+        size: row.get("size"),
+        checksum: row.get("checksum"),
+    }
+}
@@ -0,0 +1,1143 @@
+// Репозитории в памяти для тестов сервисов; повторяют поведение Postgres-реализаций
+// в том объёме, в котором на него опираются сервисы
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use crate::data::media_repository::MediaRepository;
+use crate::data::post_repository::PostRepository;
+use crate::data::series_repository::{SeriesRepository, check_series_posts};
+use crate::data::user_repository::UserRepository;
+use crate::domain::collaborator::{Collaborator, PostRole};
+use crate::domain::follow::{FollowCursor, FollowUser};
+use crate::domain::media::{Media, MediaObject, MediaStatus, MediaVariant, NewMedia};
+use crate::domain::post::{CommentMode, NewPost, Post, PostPatch, PostStatus};
+use crate::domain::post_query::PostQuery;
+use crate::domain::post_search::{PostSearchHit, PostSearchQuery};
+use crate::domain::reaction::{ReactionKind, summarize_reactions};
+use crate::domain::revision::PostRevision;
+use crate::domain::series::{NewSeries, Series, SeriesEntry};
+use crate::domain::slug::{matches_base, slugify};
+use crate::domain::{error::AuthError, error::PostError, user::NewUser, user::User};
+
+#[derive(Default)]
+pub struct MemoryUserRepository {
+    users: Mutex<Vec<User>>,
+    // (follower_id, followee_id, created_at)
+    follows: Mutex<Vec<(i64, i64, DateTime<Utc>)>>,
+}
+
+impl MemoryUserRepository {
+    pub fn delete(&self, id: i64) {
+        self.users.lock().unwrap().retain(|user| user.id != id);
+        self.follows
+            .lock()
+            .unwrap()
+            .retain(|(follower, followee, _)| *follower != id && *followee != id);
+    }
+
+    fn follow_page(
+        &self,
+        user_id: i64,
+        followers: bool,
+        after: Option<&FollowCursor>,
+        limit: i64,
+    ) -> Vec<FollowUser> {
+        let users = self.users.lock().unwrap();
+        let mut page: Vec<FollowUser> = self
+            .follows
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|&(follower, followee, created_at)| {
+                let (key, other) = if followers {
+                    (followee, follower)
+                } else {
+                    (follower, followee)
+                };
+                let user = users.iter().find(|user| user.id == other)?;
+                (key == user_id).then(|| FollowUser {
+                    id: user.id,
+                    username: user.username.clone(),
+                    followed_at: created_at,
+                })
+            })
+            .filter(|user| {
+                after.is_none_or(|c| (user.followed_at, user.id) < (c.followed_at, c.user_id))
+            })
+            .collect();
+        page.sort_by_key(|user| std::cmp::Reverse((user.followed_at, user.id)));
+        page.truncate(limit as usize);
+        page
+    }
+}
+
+#[async_trait]
+impl UserRepository for MemoryUserRepository {
+    async fn create(&self, user: NewUser) -> Result<User, AuthError> {
+        let mut users = self.users.lock().unwrap();
+        if users.iter().any(|existing| existing.email == user.email) {
+            return Err(AuthError::Validation("email already registered".into()));
+        }
+        let now = Utc::now();
+        let user = User {
+            id: users.iter().map(|user| user.id).max().unwrap_or_default() + 1,
+            username: user.username,
+            email: user.email,
+            password_hash: user.password_hash,
+            created_at: now,
+            updated_at: now,
+        };
+        users.push(user.clone());
+        Ok(user)
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, AuthError> {
+        let users = self.users.lock().unwrap();
+        Ok(users.iter().find(|user| user.email == email).cloned())
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<User>, AuthError> {
+        let users = self.users.lock().unwrap();
+        Ok(users.iter().find(|user| user.id == id).cloned())
+    }
+
+    async fn follow(&self, follower_id: i64, followee_id: i64) -> Result<bool, AuthError> {
+        let mut follows = self.follows.lock().unwrap();
+        if follows
+            .iter()
+            .any(|&(follower, followee, _)| (follower, followee) == (follower_id, followee_id))
+        {
+            return Ok(false);
+        }
+        follows.push((follower_id, followee_id, Utc::now()));
+        Ok(true)
+    }
+
+    async fn unfollow(&self, follower_id: i64, followee_id: i64) -> Result<bool, AuthError> {
+        let mut follows = self.follows.lock().unwrap();
+        let before = follows.len();
+        follows
+            .retain(|&(follower, followee, _)| (follower, followee) != (follower_id, followee_id));
+        Ok(follows.len() < before)
+    }
+
+    async fn find_followers(
+        &self,
+        user_id: i64,
+        after: Option<&FollowCursor>,
+        limit: i64,
+    ) -> Result<Vec<FollowUser>, AuthError> {
+        Ok(self.follow_page(user_id, true, after, limit))
+    }
+
+    async fn find_following(
+        &self,
+        user_id: i64,
+        after: Option<&FollowCursor>,
+        limit: i64,
+    ) -> Result<Vec<FollowUser>, AuthError> {
+        Ok(self.follow_page(user_id, false, after, limit))
+    }
+}
+
+// Посты в корзине хранятся вместе с живыми и отличаются deleted_at, как в таблице posts
+#[derive(Default)]
+pub struct MemoryPostRepository {
+    posts: Mutex<Vec<Post>>,
+    // (slug, post_id) прежних адресов постов
+    old_slugs: Mutex<Vec<(String, i64)>>,
+    // (post_id, user_id, kind)
+    reactions: Mutex<Vec<(i64, i64, ReactionKind)>>,
+    // (post_id, приглашённый соавтор)
+    collaborators: Mutex<Vec<(i64, Collaborator)>>,
+    revisions: Mutex<Vec<PostRevision>>,
+    last_query: Mutex<Option<PostQuery>>,
+}
+
+// Пост с заголовком и slug'ом по id, созданный только что
+pub fn post(id: i64, author_id: i64, status: PostStatus) -> Post {
+    let now = Utc::now();
+    Post {
+        id,
+        title: format!("Post {}", id),
+        content: String::new(),
+        content_html: String::new(),
+        author_id,
+        slug: format!("post-{}", id),
+        tags: Vec::new(),
+        reactions: Vec::new(),
+        series: None,
+        comment_mode: CommentMode::Open,
+        status,
+        created_at: now,
+        updated_at: now,
+        published_at: (status == PostStatus::Published).then_some(now),
+        publish_at: None,
+        deleted_at: None,
+        version: 1,
+    }
+}
+
+impl MemoryPostRepository {
+    pub fn insert(&self, post: Post) {
+        self.posts.lock().unwrap().push(post);
+    }
+
+    // Пост в том числе из корзины
+    pub fn stored(&self, id: i64) -> Option<Post> {
+        let posts = self.posts.lock().unwrap();
+        posts.iter().find(|post| post.id == id).cloned()
+    }
+
+    // Последний запрос find_page
+    pub fn last_query(&self) -> Option<PostQuery> {
+        self.last_query.lock().unwrap().clone()
+    }
+
+    fn with_reactions(&self, mut post: Post) -> Post {
+        let mut counts: HashMap<String, i64> = HashMap::new();
+        for (post_id, _, kind) in self.reactions.lock().unwrap().iter() {
+            if *post_id == post.id {
+                *counts.entry(kind.as_str().to_string()).or_default() += 1;
+            }
+        }
+        post.reactions = summarize_reactions(&counts);
+        post
+    }
+
+    fn live(&self, matches: impl Fn(&Post) -> bool) -> Option<Post> {
+        let posts = self.posts.lock().unwrap();
+        let post = posts
+            .iter()
+            .find(|post| post.deleted_at.is_none() && matches(post))
+            .cloned();
+        drop(posts);
+        post.map(|post| self.with_reactions(post))
+    }
+
+    // Меняет живой пост и поднимает его версию; None, если поста нет или apply отказался
+    fn change(&self, id: i64, apply: impl FnOnce(&mut Post) -> bool) -> Option<Post> {
+        let mut posts = self.posts.lock().unwrap();
+        let post = posts
+            .iter_mut()
+            .find(|post| post.id == id && post.deleted_at.is_none())?;
+        if !apply(post) {
+            return None;
+        }
+        post.version += 1;
+        post.updated_at = Utc::now();
+        let post = post.clone();
+        drop(posts);
+        Some(self.with_reactions(post))
+    }
+
+    fn is_owner(&self, post: &Post, user_id: i64) -> bool {
+        post.author_id == user_id
+            || self
+                .collaborators
+                .lock()
+                .unwrap()
+                .iter()
+                .any(|(post_id, c)| {
+                    *post_id == post.id && c.user_id == user_id && c.role == PostRole::Owner
+                })
+    }
+
+    fn can_view(&self, post: &Post, viewer_id: Option<i64>) -> bool {
+        post.status == PostStatus::Published
+            || viewer_id.is_some_and(|viewer_id| {
+                post.author_id == viewer_id
+                    || self
+                        .collaborators
+                        .lock()
+                        .unwrap()
+                        .iter()
+                        .any(|(post_id, c)| *post_id == post.id && c.user_id == viewer_id)
+            })
+    }
+
+    // Свободный slug вида base, base-2, ...; прежние slug'и самого поста считаются свободными
+    fn allocate_slug(&self, posts: &[Post], base: &str, post_id: Option<i64>) -> String {
+        let old_slugs = self.old_slugs.lock().unwrap();
+        let taken: Vec<&str> = posts
+            .iter()
+            .filter(|post| Some(post.id) != post_id)
+            .map(|post| post.slug.as_str())
+            .chain(
+                old_slugs
+                    .iter()
+                    .filter(|(_, id)| Some(*id) != post_id)
+                    .map(|(slug, _)| slug.as_str()),
+            )
+            .collect();
+        if !taken.contains(&base) {
+            return base.to_string();
+        }
+        (2..)
+            .map(|n| format!("{}-{}", base, n))
+            .find(|candidate| !taken.contains(&candidate.as_str()))
+            .expect("infinite sequence always yields a free slug")
+    }
+
+    fn next_slug(&self, posts: &[Post], id: i64, current_slug: &str, title: &str) -> String {
+        let base = slugify(title);
+        if matches_base(current_slug, &base) {
+            return current_slug.to_string();
+        }
+        let slug = self.allocate_slug(posts, &base, Some(id));
+        let mut old_slugs = self.old_slugs.lock().unwrap();
+        old_slugs.retain(|(old, _)| *old != slug);
+        old_slugs.push((current_slug.to_string(), id));
+        slug
+    }
+
+    fn add_revision(&self, post: &Post, editor_id: i64) {
+        let mut revisions = self.revisions.lock().unwrap();
+        let revision = revisions
+            .iter()
+            .filter(|revision| revision.post_id == post.id)
+            .map(|revision| revision.revision)
+            .max()
+            .unwrap_or_default()
+            + 1;
+        revisions.push(PostRevision {
+            post_id: post.id,
+            revision,
+            title: post.title.clone(),
+            content: post.content.clone(),
+            editor_id,
+            created_at: Utc::now(),
+        });
+    }
+}
+
+fn ensure_version(post: &Post, expected: Option<&[i32]>) -> Result<(), PostError> {
+    match expected {
+        Some(expected) if !expected.contains(&post.version) => {
+            Err(PostError::PreconditionFailed(format!(
+                "post {} is at version {}, not {:?}",
+                post.id, post.version, expected
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[async_trait]
+impl PostRepository for MemoryPostRepository {
+    async fn create(&self, new_post: NewPost) -> Result<Post, PostError> {
+        let mut posts = self.posts.lock().unwrap();
+        let slug = self.allocate_slug(&posts, &slugify(&new_post.title), None);
+        let post = Post {
+            title: new_post.title,
+            content: new_post.content,
+            content_html: new_post.content_html,
+            slug,
+            tags: new_post.tags.unwrap_or_default(),
+            status: new_post.status,
+            created_at: new_post.created_at,
+            updated_at: new_post.created_at,
+            published_at: (new_post.status == PostStatus::Published).then_some(new_post.created_at),
+            ..post(
+                posts.iter().map(|post| post.id).max().unwrap_or_default() + 1,
+                new_post.author_id,
+                new_post.status,
+            )
+        };
+        posts.push(post.clone());
+        drop(posts);
+        self.add_revision(&post, post.author_id);
+        Ok(self.with_reactions(post))
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Post>, PostError> {
+        Ok(self.live(|post| post.id == id))
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Post>, PostError> {
+        Ok(self.live(|post| post.slug == slug))
+    }
+
+    async fn find_by_old_slug(&self, slug: &str) -> Result<Option<Post>, PostError> {
+        let old_slugs = self.old_slugs.lock().unwrap();
+        let Some(&(_, id)) = old_slugs.iter().find(|(old, _)| old == slug) else {
+            return Ok(None);
+        };
+        drop(old_slugs);
+        Ok(self.live(|post| post.id == id))
+    }
+
+    // Фильтры и сортировка не применяются: тесты сервиса проверяют построение запроса и курсора
+    async fn find_page(&self, query: &PostQuery) -> Result<Vec<Post>, PostError> {
+        *self.last_query.lock().unwrap() = Some(query.clone());
+        let posts = self.posts.lock().unwrap();
+        Ok(posts
+            .iter()
+            .filter(|post| post.deleted_at.is_none())
+            .take(query.limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    async fn update(
+        &self,
+        id: i64,
+        new_post: NewPost,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Post>, PostError> {
+        let mut posts = self.posts.lock().unwrap();
+        let Some(index) = posts
+            .iter()
+            .position(|post| post.id == id && post.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+        ensure_version(&posts[index], expected_versions.as_deref())?;
+        let slug = self.next_slug(&posts, id, &posts[index].slug, &new_post.title);
+        let post = &mut posts[index];
+        post.title = new_post.title;
+        post.content = new_post.content;
+        post.content_html = new_post.content_html;
+        post.slug = slug;
+        if let Some(tags) = new_post.tags {
+            post.tags = tags;
+        }
+        post.version += 1;
+        post.updated_at = Utc::now();
+        let post = post.clone();
+        drop(posts);
+        self.add_revision(&post, new_post.author_id);
+        Ok(Some(self.with_reactions(post)))
+    }
+
+    async fn patch(
+        &self,
+        id: i64,
+        patch: &PostPatch,
+        editor_id: i64,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Post>, PostError> {
+        let mut posts = self.posts.lock().unwrap();
+        let Some(index) = posts
+            .iter()
+            .position(|post| post.id == id && post.deleted_at.is_none())
+        else {
+            return Ok(None);
+        };
+        ensure_version(&posts[index], expected_versions.as_deref())?;
+        let title = patch
+            .title
+            .clone()
+            .filter(|title| *title != posts[index].title);
+        let content = patch
+            .content
+            .clone()
+            .filter(|content| *content != posts[index].content);
+        if title.is_none() && content.is_none() && patch.tags.is_none() {
+            let post = posts[index].clone();
+            drop(posts);
+            return Ok(Some(self.with_reactions(post)));
+        }
+        let slug = title
+            .as_ref()
+            .map(|title| self.next_slug(&posts, id, &posts[index].slug, title));
+        let post = &mut posts[index];
+        if let Some(tags) = &patch.tags {
+            post.tags = tags.clone();
+        }
+        let revised = title.is_some() || content.is_some();
+        if let (Some(title), Some(slug)) = (title, slug) {
+            post.title = title;
+            post.slug = slug;
+        }
+        if let Some(content) = content {
+            post.content = content;
+            if let Some(content_html) = &patch.content_html {
+                post.content_html = content_html.clone();
+            }
+        }
+        post.version += 1;
+        post.updated_at = Utc::now();
+        let post = post.clone();
+        drop(posts);
+        if revised {
+            self.add_revision(&post, editor_id);
+        }
+        Ok(Some(self.with_reactions(post)))
+    }
+
+    async fn delete(
+        &self,
+        id: i64,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Post>, PostError> {
+        Ok(self.change(id, |post| {
+            if ensure_version(post, expected_versions.as_deref()).is_err() {
+                return false;
+            }
+            post.deleted_at = Some(Utc::now());
+            true
+        }))
+    }
+
+    async fn set_status(&self, id: i64, status: PostStatus) -> Result<Option<Post>, PostError> {
+        Ok(self.change(id, |post| {
+            post.status = status;
+            match status {
+                PostStatus::Published => {
+                    post.published_at = post.published_at.or(Some(Utc::now()));
+                    post.publish_at = None;
+                }
+                PostStatus::Draft => post.published_at = None,
+                PostStatus::Archived => post.publish_at = None,
+            }
+            true
+        }))
+    }
+
+    async fn set_publish_at(
+        &self,
+        id: i64,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Post>, PostError> {
+        Ok(self.change(id, |post| {
+            if post.status != PostStatus::Draft {
+                return false;
+            }
+            post.publish_at = publish_at;
+            true
+        }))
+    }
+
+    async fn set_comment_mode(
+        &self,
+        id: i64,
+        mode: CommentMode,
+    ) -> Result<Option<Post>, PostError> {
+        Ok(self.change(id, |post| {
+            post.comment_mode = mode;
+            true
+        }))
+    }
+
+    async fn add_reaction(
+        &self,
+        post_id: i64,
+        user_id: i64,
+        kind: ReactionKind,
+    ) -> Result<bool, PostError> {
+        let mut reactions = self.reactions.lock().unwrap();
+        if reactions.contains(&(post_id, user_id, kind)) {
+            return Ok(false);
+        }
+        reactions.push((post_id, user_id, kind));
+        Ok(true)
+    }
+
+    async fn remove_reaction(
+        &self,
+        post_id: i64,
+        user_id: i64,
+        kind: ReactionKind,
+    ) -> Result<bool, PostError> {
+        let mut reactions = self.reactions.lock().unwrap();
+        let before = reactions.len();
+        reactions.retain(|reaction| *reaction != (post_id, user_id, kind));
+        Ok(reactions.len() < before)
+    }
+
+    async fn find_user_reactions(
+        &self,
+        user_id: i64,
+        post_ids: &[i64],
+    ) -> Result<Vec<(i64, ReactionKind)>, PostError> {
+        let reactions = self.reactions.lock().unwrap();
+        Ok(reactions
+            .iter()
+            .filter(|(post_id, user, _)| *user == user_id && post_ids.contains(post_id))
+            .map(|&(post_id, _, kind)| (post_id, kind))
+            .collect())
+    }
+
+    async fn find_collaborator_role(
+        &self,
+        post_id: i64,
+        user_id: i64,
+    ) -> Result<Option<PostRole>, PostError> {
+        let collaborators = self.collaborators.lock().unwrap();
+        Ok(collaborators
+            .iter()
+            .find(|(post, c)| *post == post_id && c.user_id == user_id)
+            .map(|(_, c)| c.role))
+    }
+
+    async fn list_collaborators(&self, post_id: i64) -> Result<Vec<Collaborator>, PostError> {
+        let Some(post) = self.stored(post_id) else {
+            return Ok(Vec::new());
+        };
+        let owner = Collaborator {
+            user_id: post.author_id,
+            username: format!("user{}", post.author_id),
+            role: PostRole::Owner,
+            invited_by: None,
+            created_at: post.created_at,
+        };
+        let collaborators = self.collaborators.lock().unwrap();
+        Ok(std::iter::once(owner)
+            .chain(
+                collaborators
+                    .iter()
+                    .filter(|(post, _)| *post == post_id)
+                    .map(|(_, c)| c.clone()),
+            )
+            .collect())
+    }
+
+    // Пользователи здесь не хранятся, поэтому приглашение проходит для любого id
+    async fn upsert_collaborator(
+        &self,
+        post_id: i64,
+        user_id: i64,
+        role: PostRole,
+        invited_by: i64,
+    ) -> Result<Option<Collaborator>, PostError> {
+        let mut collaborators = self.collaborators.lock().unwrap();
+        if let Some((_, existing)) = collaborators
+            .iter_mut()
+            .find(|(post, c)| *post == post_id && c.user_id == user_id)
+        {
+            existing.role = role;
+            return Ok(Some(existing.clone()));
+        }
+        let collaborator = Collaborator {
+            user_id,
+            username: format!("user{}", user_id),
+            role,
+            invited_by: Some(invited_by),
+            created_at: Utc::now(),
+        };
+        collaborators.push((post_id, collaborator.clone()));
+        Ok(Some(collaborator))
+    }
+
+    async fn remove_collaborator(&self, post_id: i64, user_id: i64) -> Result<bool, PostError> {
+        let mut collaborators = self.collaborators.lock().unwrap();
+        let before = collaborators.len();
+        collaborators.retain(|(post, c)| !(*post == post_id && c.user_id == user_id));
+        Ok(collaborators.len() < before)
+    }
+
+    async fn find_trash(&self, owner_id: i64) -> Result<Vec<Post>, PostError> {
+        let mut trash: Vec<Post> = self
+            .posts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|post| post.deleted_at.is_some())
+            .cloned()
+            .collect();
+        trash.retain(|post| self.is_owner(post, owner_id));
+        trash.sort_by_key(|post| std::cmp::Reverse((post.deleted_at, post.id)));
+        Ok(trash)
+    }
+
+    async fn restore(
+        &self,
+        id: i64,
+        owner_id: i64,
+        deleted_after: DateTime<Utc>,
+    ) -> Result<Option<Post>, PostError> {
+        let Some(post) = self.stored(id) else {
+            return Ok(None);
+        };
+        let restorable = post
+            .deleted_at
+            .is_some_and(|deleted_at| deleted_at > deleted_after)
+            && self.is_owner(&post, owner_id);
+        if !restorable {
+            return Ok(None);
+        }
+        let mut posts = self.posts.lock().unwrap();
+        let post = posts.iter_mut().find(|post| post.id == id).unwrap();
+        post.deleted_at = None;
+        post.version += 1;
+        post.updated_at = Utc::now();
+        let post = post.clone();
+        drop(posts);
+        Ok(Some(self.with_reactions(post)))
+    }
+
+    async fn purge_deleted(
+        &self,
+        deleted_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, PostError> {
+        let mut posts = self.posts.lock().unwrap();
+        let mut expired: Vec<(DateTime<Utc>, i64)> = posts
+            .iter()
+            .filter_map(|post| Some((post.deleted_at?, post.id)))
+            .filter(|(deleted_at, _)| *deleted_at <= deleted_before)
+            .collect();
+        expired.sort();
+        expired.truncate(limit as usize);
+        posts.retain(|post| !expired.iter().any(|(_, id)| *id == post.id));
+        drop(posts);
+        // Как каскадное удаление в БД
+        let purged = |post_id: &i64| expired.iter().any(|(_, id)| id == post_id);
+        self.reactions
+            .lock()
+            .unwrap()
+            .retain(|(post_id, _, _)| !purged(post_id));
+        self.collaborators
+            .lock()
+            .unwrap()
+            .retain(|(post_id, _)| !purged(post_id));
+        self.revisions
+            .lock()
+            .unwrap()
+            .retain(|revision| !purged(&revision.post_id));
+        self.old_slugs
+            .lock()
+            .unwrap()
+            .retain(|(_, post_id)| !purged(post_id));
+        Ok(expired.len() as u64)
+    }
+
+    // NULL в content_html здесь не выразить, не отрендеренным считается пустой HTML
+    async fn find_without_html(&self, limit: i64) -> Result<Vec<Post>, PostError> {
+        let mut posts: Vec<Post> = self
+            .posts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|post| post.content_html.is_empty())
+            .cloned()
+            .collect();
+        posts.sort_by_key(|post| post.id);
+        posts.truncate(limit as usize);
+        Ok(posts)
+    }
+
+    // Язык поиска в памяти не хранится, переиндексировать нечего
+    async fn reindex_search(&self) -> Result<u64, PostError> {
+        Ok(0)
+    }
+
+    async fn backfill_slugs(&self) -> Result<u64, PostError> {
+        let mut posts = self.posts.lock().unwrap();
+        let mut updated = 0;
+        for index in 0..posts.len() {
+            let (id, current_slug, title) = {
+                let post = &posts[index];
+                (post.id, post.slug.clone(), post.title.clone())
+            };
+            if current_slug != format!("post-{}", id) {
+                continue;
+            }
+            let slug = self.next_slug(&posts, id, &current_slug, &title);
+            if slug != current_slug {
+                posts[index].slug = slug;
+                updated += 1;
+            }
+        }
+        Ok(updated)
+    }
+
+    async fn set_content_html(&self, id: i64, content_html: &str) -> Result<(), PostError> {
+        let mut posts = self.posts.lock().unwrap();
+        if let Some(post) = posts
+            .iter_mut()
+            .find(|post| post.id == id && post.content_html.is_empty())
+        {
+            post.content_html = content_html.to_string();
+        }
+        Ok(())
+    }
+
+    async fn publish_due(&self, limit: i64) -> Result<Vec<Post>, PostError> {
+        let now = Utc::now();
+        let mut posts = self.posts.lock().unwrap();
+        let mut due: Vec<(DateTime<Utc>, i64)> = posts
+            .iter()
+            .filter(|post| post.status == PostStatus::Draft && post.deleted_at.is_none())
+            .filter_map(|post| Some((post.publish_at?, post.id)))
+            .filter(|(publish_at, _)| *publish_at <= now)
+            .collect();
+        due.sort();
+        due.truncate(limit as usize);
+        let mut published = Vec::new();
+        for post in posts.iter_mut() {
+            if due.iter().any(|(_, id)| *id == post.id) {
+                post.status = PostStatus::Published;
+                post.published_at = post.published_at.or(post.publish_at);
+                post.publish_at = None;
+                post.version += 1;
+                post.updated_at = now;
+                published.push(post.clone());
+            }
+        }
+        Ok(published)
+    }
+
+    // Вместо полнотекстового поиска — вхождение подстроки без учёта регистра;
+    // совпадение в заголовке весит больше, чем в тексте
+    async fn search(&self, query: &PostSearchQuery) -> Result<Vec<PostSearchHit>, PostError> {
+        let text = query.text.to_lowercase();
+        let posts: Vec<Post> = self
+            .posts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|post| post.deleted_at.is_none())
+            .cloned()
+            .collect();
+        let mut hits: Vec<PostSearchHit> = posts
+            .into_iter()
+            .filter(|post| self.can_view(post, query.viewer_id))
+            .filter_map(|post| {
+                let rank = if post.title.to_lowercase().contains(&text) {
+                    1.0
+                } else if post.content.to_lowercase().contains(&text) {
+                    0.5
+                } else {
+                    return None;
+                };
+                Some(PostSearchHit {
+                    title_highlight: post.title.clone(),
+                    snippet: post.content.clone(),
+                    post,
+                    rank,
+                })
+            })
+            .filter(|hit| {
+                query
+                    .after
+                    .is_none_or(|after| (hit.rank, hit.post.id) < (after.rank, after.id))
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            (b.rank, b.post.id)
+                .partial_cmp(&(a.rank, a.post.id))
+                .unwrap()
+        });
+        hits.truncate(query.limit as usize);
+        Ok(hits)
+    }
+
+    async fn list_revisions(&self, post_id: i64) -> Result<Vec<PostRevision>, PostError> {
+        let mut revisions: Vec<PostRevision> = self
+            .revisions
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|revision| revision.post_id == post_id)
+            .cloned()
+            .collect();
+        revisions.sort_by_key(|revision| std::cmp::Reverse(revision.revision));
+        Ok(revisions)
+    }
+
+    async fn find_revision(
+        &self,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<Option<PostRevision>, PostError> {
+        let revisions = self.revisions.lock().unwrap();
+        Ok(revisions
+            .iter()
+            .find(|r| r.post_id == post_id && r.revision == revision)
+            .cloned())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryMediaRepository {
+    media: Mutex<Vec<Media>>,
+    variants: Mutex<Vec<MediaVariant>>,
+}
+
+#[async_trait]
+impl MediaRepository for MemoryMediaRepository {
+    async fn create_within_quota(
+        &self,
+        new_media: NewMedia,
+        quota: i64,
+    ) -> Result<Option<Media>, PostError> {
+        let mut media = self.media.lock().unwrap();
+        let used: i64 = media
+            .iter()
+            .filter(|m| m.owner_id == new_media.owner_id)
+            .map(|m| m.size)
+            .sum();
+        if used + new_media.size > quota {
+            return Ok(None);
+        }
+        let created = Media {
+            id: media.iter().map(|m| m.id).max().unwrap_or_default() + 1,
+            owner_id: new_media.owner_id,
+            key: new_media.key,
+            mime_type: new_media.mime_type,
+            size: new_media.size,
+            checksum: new_media.checksum,
+            width: None,
+            height: None,
+            status: MediaStatus::Processing,
+            created_at: Utc::now(),
+        };
+        media.push(created.clone());
+        Ok(Some(created))
+    }
+
+    async fn used_bytes(&self, owner_id: i64) -> Result<i64, PostError> {
+        let media = self.media.lock().unwrap();
+        Ok(media
+            .iter()
+            .filter(|m| m.owner_id == owner_id)
+            .map(|m| m.size)
+            .sum())
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Media>, PostError> {
+        let media = self.media.lock().unwrap();
+        Ok(media.iter().find(|m| m.id == id).cloned())
+    }
+
+    async fn find_object(&self, key: &str) -> Result<Option<MediaObject>, PostError> {
+        let media = self.media.lock().unwrap();
+        let variants = self.variants.lock().unwrap();
+        Ok(media
+            .iter()
+            .filter(|m| m.key == key)
+            .map(|m| (&m.mime_type, &m.checksum))
+            .chain(
+                variants
+                    .iter()
+                    .filter(|v| v.key == key)
+                    .map(|v| (&v.mime_type, &v.checksum)),
+            )
+            .map(|(mime_type, checksum)| MediaObject {
+                mime_type: mime_type.clone(),
+                checksum: checksum.clone(),
+            })
+            .next())
+    }
+
+    async fn find_by_owner(&self, owner_id: i64) -> Result<Vec<Media>, PostError> {
+        let mut owned: Vec<Media> = self
+            .media
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|m| m.owner_id == owner_id)
+            .cloned()
+            .collect();
+        owned.sort_by_key(|m| std::cmp::Reverse((m.created_at, m.id)));
+        Ok(owned)
+    }
+
+    async fn find_variants(&self, media_ids: &[i64]) -> Result<Vec<MediaVariant>, PostError> {
+        let mut variants: Vec<MediaVariant> = self
+            .variants
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|v| media_ids.contains(&v.media_id))
+            .cloned()
+            .collect();
+        variants.sort_by(|a, b| {
+            (a.media_id, a.width, &a.mime_type).cmp(&(b.media_id, b.width, &b.mime_type))
+        });
+        Ok(variants)
+    }
+
+    async fn find_processing(&self) -> Result<Vec<Media>, PostError> {
+        let media = self.media.lock().unwrap();
+        Ok(media
+            .iter()
+            .filter(|m| m.status == MediaStatus::Processing)
+            .cloned()
+            .collect())
+    }
+
+    async fn complete_processing(
+        &self,
+        media_id: i64,
+        width: i32,
+        height: i32,
+        variants: &[MediaVariant],
+    ) -> Result<Vec<String>, PostError> {
+        let mut stored = self.variants.lock().unwrap();
+        let old_keys: Vec<String> = stored
+            .iter()
+            .filter(|v| v.media_id == media_id)
+            .map(|v| v.key.clone())
+            .collect();
+        stored.retain(|v| v.media_id != media_id);
+        stored.extend(variants.iter().map(|variant| MediaVariant {
+            media_id,
+            ..variant.clone()
+        }));
+        drop(stored);
+        if let Some(media) = self
+            .media
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|m| m.id == media_id)
+        {
+            media.width = Some(width);
+            media.height = Some(height);
+            media.status = MediaStatus::Ready;
+        }
+        Ok(old_keys
+            .into_iter()
+            .filter(|key| !variants.iter().any(|variant| &variant.key == key))
+            .collect())
+    }
+
+    async fn mark_failed(&self, media_id: i64) -> Result<(), PostError> {
+        if let Some(media) = self
+            .media
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|m| m.id == media_id)
+        {
+            media.status = MediaStatus::Failed;
+        }
+        Ok(())
+    }
+}
+
+// Части серий ссылаются на посты общего репозитория постов, как series_posts на posts
+pub struct MemorySeriesRepository {
+    posts: Arc<MemoryPostRepository>,
+    series: Mutex<Vec<Series>>,
+    // (post_id, series_id, position)
+    parts: Mutex<Vec<(i64, i64, i32)>>,
+}
+
+impl MemorySeriesRepository {
+    pub fn new(posts: Arc<MemoryPostRepository>) -> Self {
+        Self {
+            posts,
+            series: Mutex::default(),
+            parts: Mutex::default(),
+        }
+    }
+}
+
+#[async_trait]
+impl SeriesRepository for MemorySeriesRepository {
+    async fn create(&self, new_series: NewSeries) -> Result<Series, PostError> {
+        let mut series = self.series.lock().unwrap();
+        let now = Utc::now();
+        let created = Series {
+            id: series.iter().map(|s| s.id).max().unwrap_or_default() + 1,
+            owner_id: new_series.owner_id,
+            title: new_series.title,
+            description: new_series.description,
+            created_at: now,
+            updated_at: now,
+        };
+        series.push(created.clone());
+        Ok(created)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Series>, PostError> {
+        let series = self.series.lock().unwrap();
+        Ok(series.iter().find(|s| s.id == id).cloned())
+    }
+
+    async fn update(
+        &self,
+        id: i64,
+        title: &str,
+        description: &str,
+    ) -> Result<Option<Series>, PostError> {
+        let mut series = self.series.lock().unwrap();
+        Ok(series.iter_mut().find(|s| s.id == id).map(|s| {
+            s.title = title.to_string();
+            s.description = description.to_string();
+            s.updated_at = Utc::now();
+            s.clone()
+        }))
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool, PostError> {
+        let mut series = self.series.lock().unwrap();
+        let before = series.len();
+        series.retain(|s| s.id != id);
+        self.parts
+            .lock()
+            .unwrap()
+            .retain(|&(_, series_id, _)| series_id != id);
+        Ok(series.len() < before)
+    }
+
+    async fn entries(
+        &self,
+        series_id: i64,
+        include_unpublished: bool,
+    ) -> Result<Vec<SeriesEntry>, PostError> {
+        let mut parts: Vec<(i64, i32)> = self
+            .parts
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|&&(_, series, _)| series == series_id)
+            .map(|&(post_id, _, position)| (post_id, position))
+            .collect();
+        parts.sort_by_key(|&(_, position)| position);
+        Ok(parts
+            .into_iter()
+            .filter_map(|(post_id, position)| {
+                let post = self.posts.stored(post_id)?;
+                let visible = post.deleted_at.is_none()
+                    && (include_unpublished || post.status == PostStatus::Published);
+                visible.then_some(SeriesEntry {
+                    position,
+                    id: post.id,
+                    title: post.title,
+                    slug: post.slug,
+                    status: post.status,
+                    published_at: post.published_at,
+                })
+            })
+            .collect())
+    }
+
+    async fn set_posts(
+        &self,
+        series_id: i64,
+        owner_id: i64,
+        post_ids: &[i64],
+    ) -> Result<(), PostError> {
+        let owned: Vec<i64> = post_ids
+            .iter()
+            .copied()
+            .filter(|&id| {
+                self.posts
+                    .stored(id)
+                    .is_some_and(|post| post.author_id == owner_id && post.deleted_at.is_none())
+            })
+            .collect();
+        let mut parts = self.parts.lock().unwrap();
+        let taken = parts
+            .iter()
+            .find(|(post_id, series, _)| *series != series_id && post_ids.contains(post_id))
+            .map(|&(post_id, series, _)| (post_id, series));
+        check_series_posts(post_ids, &owned, taken)?;
+        parts.retain(|&(_, series, _)| series != series_id);
+        parts.extend(
+            post_ids
+                .iter()
+                .zip(1..)
+                .map(|(&post_id, position)| (post_id, series_id, position)),
+        );
+        Ok(())
+    }
+}
@@ -1,2 +1,9 @@
 pub mod user_repository;
-pub mod post_repository;
\ No newline at end of file
+pub mod post_repository;
+pub mod tag_repository;
+pub mod comment_repository;
+pub mod media_repository;
+pub mod series_repository;
+pub mod analytics_repository;
+#[cfg(test)]
+pub mod memory;
\ No newline at end of file
@@ -1,76 +1,228 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
-use sqlx::{PgPool, Row};
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, QueryBuilder, Row, Transaction};
 use tracing;
 
-use crate::domain::{error::PostError, post::NewPost, post::Post};
+use crate::domain::collaborator::{Collaborator, PostRole};
+use crate::domain::post::CommentMode;
+use crate::domain::post_query::{PostCursor, PostQuery, PostSortField};
+use crate::domain::post_search::{
+    HIGHLIGHT_START, HIGHLIGHT_STOP, PostSearchHit, PostSearchQuery, highlight_to_html,
+};
+use crate::domain::reaction::{ReactionKind, summarize_reactions};
+use crate::domain::revision::PostRevision;
+use crate::domain::series::{SeriesPlacement, series_navigation};
+use crate::domain::slug::{matches_base, slugify};
+use crate::domain::{
+    error::PostError, post::NewPost, post::Post, post::PostPatch, post::PostStatus,
+};
 
 #[async_trait]
 pub trait PostRepository: Send + Sync {
     async fn create(&self, post: NewPost) -> Result<Post, PostError>;
     async fn find_by_id(&self, id: i64) -> Result<Option<Post>, PostError>;
-    async fn find_all(&self) -> Result<Option<Vec<Post>>, PostError>;
-    async fn update(&self, id: i64, post: NewPost) -> Result<Option<Post>, PostError>;
-    async fn delete(&self, id: i64) -> Result<Option<Post>, PostError>;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Post>, PostError>;
+    async fn find_by_old_slug(&self, slug: &str) -> Result<Option<Post>, PostError>;
+    async fn find_page(&self, query: &PostQuery) -> Result<Vec<Post>, PostError>;
+    // expected_versions: None — без проверки версии, иначе PreconditionFailed,
+    // если текущая версия не входит в список
+    async fn update(
+        &self,
+        id: i64,
+        post: NewPost,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Post>, PostError>;
+    // Меняет только переданные и действительно изменившиеся поля
+    async fn patch(
+        &self,
+        id: i64,
+        patch: &PostPatch,
+        editor_id: i64,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Post>, PostError>;
+    // None, если поста нет или его версия не входит в expected_versions
+    async fn delete(
+        &self,
+        id: i64,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Post>, PostError>;
+    async fn set_status(&self, id: i64, status: PostStatus) -> Result<Option<Post>, PostError>;
+    async fn set_publish_at(
+        &self,
+        id: i64,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Post>, PostError>;
+    async fn set_comment_mode(&self, id: i64, mode: CommentMode)
+    -> Result<Option<Post>, PostError>;
+    async fn add_reaction(
+        &self,
+        post_id: i64,
+        user_id: i64,
+        kind: ReactionKind,
+    ) -> Result<bool, PostError>;
+    async fn remove_reaction(
+        &self,
+        post_id: i64,
+        user_id: i64,
+        kind: ReactionKind,
+    ) -> Result<bool, PostError>;
+    async fn find_user_reactions(
+        &self,
+        user_id: i64,
+        post_ids: &[i64],
+    ) -> Result<Vec<(i64, ReactionKind)>, PostError>;
+    // Роль приглашённого соавтора; авторство поста здесь не учитывается
+    async fn find_collaborator_role(
+        &self,
+        post_id: i64,
+        user_id: i64,
+    ) -> Result<Option<PostRole>, PostError>;
+    // Автор поста первым, затем приглашённые в порядке приглашения
+    async fn list_collaborators(&self, post_id: i64) -> Result<Vec<Collaborator>, PostError>;
+    // Приглашает пользователя или меняет его роль; None, если такого пользователя нет
+    async fn upsert_collaborator(
+        &self,
+        post_id: i64,
+        user_id: i64,
+        role: PostRole,
+        invited_by: i64,
+    ) -> Result<Option<Collaborator>, PostError>;
+    async fn remove_collaborator(&self, post_id: i64, user_id: i64) -> Result<bool, PostError>;
+    // Корзина владельца: его собственные посты и посты, где он соавтор с ролью owner
+    async fn find_trash(&self, owner_id: i64) -> Result<Vec<Post>, PostError>;
+    // Восстанавливает пост из корзины, если он удалён не раньше deleted_after
+    async fn restore(
+        &self,
+        id: i64,
+        owner_id: i64,
+        deleted_after: DateTime<Utc>,
+    ) -> Result<Option<Post>, PostError>;
+    async fn purge_deleted(
+        &self,
+        deleted_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, PostError>;
+    async fn find_without_html(&self, limit: i64) -> Result<Vec<Post>, PostError>;
+    // Переиндексирует посты, сохранённые с другим языком поиска; возвращает их число
+    async fn reindex_search(&self) -> Result<u64, PostError>;
+    // Заменяет временные slug'и вида post-<id> на slug из заголовка; возвращает число постов
+    async fn backfill_slugs(&self) -> Result<u64, PostError>;
+    // Не перезаписывает HTML, сохранённый после выборки поста
+    async fn set_content_html(&self, id: i64, content_html: &str) -> Result<(), PostError>;
+    async fn publish_due(&self, limit: i64) -> Result<Vec<Post>, PostError>;
+    async fn search(&self, query: &PostSearchQuery) -> Result<Vec<PostSearchHit>, PostError>;
+    async fn list_revisions(&self, post_id: i64) -> Result<Vec<PostRevision>, PostError>;
+    async fn find_revision(
+        &self,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<Option<PostRevision>, PostError>;
+}
+
+// Теги, счётчики реакций и место в серии собираются подзапросами,
+// поэтому источник строк в запросах всегда называется posts
+macro_rules! post_columns {
+    ($($series:literal),+ $(,)?) => {
+        concat!(
+            "id, title, content, content_html, author_id, slug, comment_mode, status, created_at, updated_at, published_at, publish_at, deleted_at, version, ",
+            "ARRAY(SELECT t.name FROM post_tags pt JOIN tags t ON t.id = pt.tag_id ",
+            "WHERE pt.post_id = posts.id ORDER BY t.name) AS tags, ",
+            "(SELECT COALESCE(jsonb_object_agg(r.kind, r.total), '{}'::jsonb) FROM ",
+            "(SELECT kind, COUNT(*) AS total FROM post_reactions WHERE post_id = posts.id GROUP BY kind) r",
+            ") AS reactions, ",
+            $($series,)+
+            " AS series"
+        )
+    };
 }
 
+// Место в серии с соседними частями нужно только там, где возвращается один пост
+const POST_COLUMNS: &str = post_columns!(
+    "(SELECT jsonb_build_object('id', s.id, 'title', s.title, 'position', sp.position, ",
+    "'parts', (SELECT COALESCE(jsonb_agg(jsonb_build_object('id', p.id, 'slug', p.slug, ",
+    "'title', p.title, 'position', n.position, ",
+    "'published', p.status = 'published' AND p.deleted_at IS NULL)), '[]'::jsonb) ",
+    "FROM series_posts n JOIN posts p ON p.id = n.post_id WHERE n.series_id = sp.series_id)) ",
+    "FROM series_posts sp JOIN series s ON s.id = sp.series_id WHERE sp.post_id = posts.id)",
+);
+// В списках серия не подгружается, чтобы не выполнять подзапрос на каждую строку
+const POST_LIST_COLUMNS: &str = post_columns!("NULL::jsonb");
+
 #[derive(Clone)]
 pub struct PostgresPostRepository {
     pool: PgPool,
+    search_language: String,
 }
 
 impl PostgresPostRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, search_language: String) -> Self {
+        Self {
+            pool,
+            search_language,
+        }
     }
 }
 
 #[async_trait]
 impl PostRepository for PostgresPostRepository {
     async fn create(&self, post: NewPost) -> Result<Post, PostError> {
-        let row = sqlx::query(
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            tracing::error!("failed to start transaction: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let slug = allocate_slug(&mut tx, &slugify(&post.title), None).await?;
+        let row = sqlx::query(&format!(
             r#"
-            INSERT INTO posts (title, content, author_id, created_at)
-            VALUES ($1, $2, $3, $4)
-            RETURNING id, title, content, author_id, created_at
-            "#,
-        )
+            INSERT INTO posts (title, content, content_html, author_id, slug, status, created_at, published_at, search_language)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, CASE WHEN $6 = 'published' THEN $7 END, $8::regconfig)
+            RETURNING {POST_COLUMNS}
+            "#
+        ))
         .bind(&post.title)
         .bind(&post.content)
-        .bind(&post.author_id)
-        .bind(&post.created_at)
-        .fetch_one(&self.pool)
+        .bind(&post.content_html)
+        .bind(post.author_id)
+        .bind(&slug)
+        .bind(post.status.as_str())
+        .bind(post.created_at)
+        .bind(&self.search_language)
+        .fetch_one(&mut *tx)
         .await
         .map_err(|e| {
             tracing::error!("failed to create post: {}", e);
-            if e.as_database_error()
-                .and_then(|db| db.constraint())
-                .map(|c| c.contains("users_email"))
-                == Some(true)
-            {
-                PostError::Validation("email already registered".into())
+            // allocate_slug не видит незакоммиченные вставки с другим base, дающие тот же slug
+            if e.as_database_error().and_then(|db| db.constraint()) == Some("idx_posts_slug") {
+                PostError::InvalidState(format!("slug {} is already taken", slug))
             } else {
                 PostError::Internal(format!("database error: {}", e))
             }
         })?;
-        let post_dto = Post {
-            id: row.get("id"),
-            title: row.get("title"),
-            content: row.get("content"),
-            author_id: row.get("author_id"),
-            created_at: chrono::Utc::now(),
-        };
+        let mut post_dto = row_to_post(&row);
+        if let Some(tags) = &post.tags {
+            set_post_tags(&mut tx, post_dto.id, tags).await?;
+            post_dto.tags = tags.clone();
+        }
+        insert_revision(&mut tx, &post_dto, post_dto.author_id).await?;
+        tx.commit().await.map_err(|e| {
+            tracing::error!("failed to commit post creation: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
         tracing::info!(post_id = %post_dto.id, title = %post_dto.title, "post created");
         Ok(post_dto)
     }
 
     async fn find_by_id(&self, _id: i64) -> Result<Option<Post>, PostError> {
-        let row = sqlx::query(
+        let row = sqlx::query(&format!(
             r#"
-            SELECT id, title, content, author_id, created_at
+            SELECT {POST_COLUMNS}
             FROM posts
-            WHERE id = $1
-            "#,
-        )
+            WHERE id = $1 AND deleted_at IS NULL
+            "#
+        ))
         .bind(_id)
         .fetch_optional(&self.pool)
         .await
@@ -79,13 +231,7 @@ impl PostRepository for PostgresPostRepository {
             PostError::Internal(format!("database error: {}", e))
         })?;
         if let Some(row) = row {
-            let post = Post {
-                id: row.get("id"),
-                title: row.get("title"),
-                content: row.get("content"),
-                author_id: row.get("author_id"),
-                created_at: row.get("created_at"),
-            };
+            let post = row_to_post(&row);
             tracing::info!(post_id = %post.id, title = %post.title, "fetched post");
             Ok(Some(post))
         } else {
@@ -94,76 +240,288 @@ impl PostRepository for PostgresPostRepository {
         }
     }
 
-    async fn find_all(&self) -> Result<Option<Vec<Post>>, PostError> {
-        let rows = sqlx::query(
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Post>, PostError> {
+        let row = sqlx::query(&format!(
             r#"
-            SELECT id, title, content, author_id, created_at
+            SELECT {POST_COLUMNS}
             FROM posts
-            "#,
-        )
-        .fetch_all(&self.pool)
+            WHERE slug = $1 AND deleted_at IS NULL
+            "#
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
         .await
         .map_err(|e| {
+            tracing::error!("failed to find post by slug: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(row.as_ref().map(row_to_post))
+    }
+
+    async fn find_by_old_slug(&self, slug: &str) -> Result<Option<Post>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            SELECT {POST_COLUMNS}
+            FROM posts
+            WHERE id = (SELECT post_id FROM post_slug_history WHERE slug = $1)
+              AND deleted_at IS NULL
+            "#
+        ))
+        .bind(slug)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to find post by old slug: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(row.as_ref().map(row_to_post))
+    }
+
+    async fn find_page(&self, query: &PostQuery) -> Result<Vec<Post>, PostError> {
+        let mut qb = QueryBuilder::<Postgres>::new(format!(
+            "SELECT {POST_LIST_COLUMNS} FROM posts WHERE deleted_at IS NULL"
+        ));
+        match query.filter.viewer_id {
+            Some(viewer_id) => {
+                qb.push(" AND (status = 'published' OR author_id = ")
+                    .push_bind(viewer_id)
+                    .push(" OR id IN (SELECT post_id FROM post_collaborators WHERE user_id = ")
+                    .push_bind(viewer_id)
+                    .push("))");
+            }
+            None => {
+                qb.push(" AND status = 'published'");
+            }
+        }
+        if let Some(author_id) = query.filter.author_id {
+            qb.push(" AND author_id = ").push_bind(author_id);
+        }
+        if let Some(status) = query.filter.status {
+            // Литерал, а не параметр: с параметром планировщик не берёт частичные индексы по status
+            qb.push(format!(" AND status = '{}'", status.as_str()));
+        }
+        if let Some(tag) = &query.filter.tag {
+            qb.push(
+                " AND EXISTS (SELECT 1 FROM post_tags pt JOIN tags t ON t.id = pt.tag_id \
+                 WHERE pt.post_id = posts.id AND t.name = ",
+            )
+            .push_bind(tag.clone())
+            .push(")");
+        }
+        if let Some(follower_id) = query.filter.followed_by {
+            qb.push(" AND author_id IN (SELECT followee_id FROM follows WHERE follower_id = ")
+                .push_bind(follower_id)
+                .push(")");
+        }
+        if let Some(created_after) = query.filter.created_after {
+            qb.push(" AND created_at > ").push_bind(created_after);
+        }
+        if let Some(created_before) = query.filter.created_before {
+            qb.push(" AND created_at < ").push_bind(created_before);
+        }
+        if let Some(cursor) = &query.after {
+            // (k1 > v1) OR (k1 = v1 AND k2 > v2) OR ... с учётом направления каждого ключа
+            qb.push(" AND (");
+            for (i, key) in query.sort.iter().enumerate() {
+                if i > 0 {
+                    qb.push(" OR ");
+                }
+                qb.push("(");
+                for prev in &query.sort[..i] {
+                    qb.push(prev.field.name()).push(" = ");
+                    push_cursor_value(&mut qb, prev.field, cursor);
+                    qb.push(" AND ");
+                }
+                qb.push(key.field.name())
+                    .push(if key.descending { " < " } else { " > " });
+                push_cursor_value(&mut qb, key.field, cursor);
+                qb.push(")");
+            }
+            qb.push(")");
+        }
+        qb.push(" ORDER BY ");
+        for (i, key) in query.sort.iter().enumerate() {
+            if i > 0 {
+                qb.push(", ");
+            }
+            qb.push(key.field.name())
+                .push(if key.descending { " DESC" } else { " ASC" });
+        }
+        qb.push(" LIMIT ").push_bind(query.limit);
+
+        let rows = qb.build().fetch_all(&self.pool).await.map_err(|e| {
             tracing::error!("failed to fetch posts: {}", e);
             PostError::Internal(format!("database error: {}", e))
         })?;
-        let posts: Vec<Post> = rows
-            .into_iter()
-            .map(|row| Post {
-                id: row.get("id"),
-                title: row.get("title"),
-                content: row.get("content"),
-                author_id: row.get("author_id"),
-                created_at: row.get("created_at"),
-            })
-            .collect();
+        let posts: Vec<Post> = rows.into_iter().map(|row| row_to_post(&row)).collect();
         tracing::info!("fetched {} posts", posts.len());
-        Ok(Some(posts))
+        Ok(posts)
     }
 
-    async fn update(&self, id: i64, post: NewPost) -> Result<Option<Post>, PostError> {
-        let row = sqlx::query(
+    async fn update(
+        &self,
+        id: i64,
+        post: NewPost,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Post>, PostError> {
+        // Строка поста блокируется UPDATE'ом, поэтому номера ревизий выдаются без гонок
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            tracing::error!("failed to start transaction: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let current: Option<(String, i32)> = sqlx::query_as(
+            "SELECT slug, version FROM posts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to lock post for update: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let Some((current_slug, current_version)) = current else {
+            tracing::info!("post {} not found for update", id);
+            return Ok(None);
+        };
+        ensure_version(id, current_version, expected_versions.as_deref())?;
+        let slug = next_slug(&mut tx, id, current_slug, &post.title).await?;
+        if let Some(tags) = &post.tags {
+            set_post_tags(&mut tx, id, tags).await?;
+        }
+        let row = sqlx::query(&format!(
             r#"
             UPDATE posts
-            SET title = $1, content = $2
-            WHERE id = $3
-            RETURNING id, title, content, author_id, created_at
-            "#,
-        )
+            SET title = $1, content = $2, content_html = $3, slug = $4, version = version + 1
+            WHERE id = $5
+            RETURNING {POST_COLUMNS}
+            "#
+        ))
         .bind(&post.title)
         .bind(&post.content)
+        .bind(&post.content_html)
+        .bind(&slug)
         .bind(id)
-        .fetch_optional(&self.pool)
+        .fetch_optional(&mut *tx)
         .await
         .map_err(|e| {
             tracing::error!("failed to update post: {}", e);
             PostError::Internal(format!("database error: {}", e))
         })?;
         if let Some(row) = row {
-            let post = Post {
-                id: row.get("id"),
-                title: row.get("title"),
-                content: row.get("content"),
-                author_id: row.get("author_id"),
-                created_at: row.get("created_at"),
-            };
-            tracing::info!(post_id = %post.id, title = %post.title, "post updated");
-            Ok(Some(post))
+            let updated = row_to_post(&row);
+            let revision = insert_revision(&mut tx, &updated, post.author_id).await?;
+            tx.commit().await.map_err(|e| {
+                tracing::error!("failed to commit post update: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+            tracing::info!(post_id = %updated.id, title = %updated.title, revision, "post updated");
+            Ok(Some(updated))
         } else {
             tracing::info!("post {} not found for update", id);
             Ok(None)
         }
     }
 
-    async fn delete(&self, _id: i64) -> Result<Option<Post>, PostError> {
-        let row = sqlx::query(
-            r#"
-            DELETE FROM posts
-            WHERE id = $1
-            RETURNING id, title, content, author_id, created_at
-            "#,
+    async fn patch(
+        &self,
+        id: i64,
+        patch: &PostPatch,
+        editor_id: i64,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Post>, PostError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            tracing::error!("failed to start transaction: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let current: Option<(String, String, String, i32)> = sqlx::query_as(
+            "SELECT slug, title, content, version FROM posts WHERE id = $1 AND deleted_at IS NULL FOR UPDATE",
         )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to lock post for patch: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let Some((current_slug, current_title, current_content, current_version)) = current else {
+            tracing::info!("post {} not found for patch", id);
+            return Ok(None);
+        };
+        ensure_version(id, current_version, expected_versions.as_deref())?;
+
+        // Поля, совпадающие с текущими значениями, в UPDATE не попадают
+        let title = patch
+            .title
+            .as_ref()
+            .filter(|title| **title != current_title);
+        let content = patch
+            .content
+            .as_ref()
+            .filter(|content| **content != current_content);
+        if title.is_none() && content.is_none() && patch.tags.is_none() {
+            let row = sqlx::query(&format!("SELECT {POST_COLUMNS} FROM posts WHERE id = $1"))
+                .bind(id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| {
+                    tracing::error!("failed to fetch post: {}", e);
+                    PostError::Internal(format!("database error: {}", e))
+                })?;
+            return Ok(Some(row_to_post(&row)));
+        }
+
+        if let Some(tags) = &patch.tags {
+            set_post_tags(&mut tx, id, tags).await?;
+        }
+        let mut qb = QueryBuilder::<Postgres>::new("UPDATE posts SET version = version + 1");
+        if let Some(title) = title {
+            let slug = next_slug(&mut tx, id, current_slug, title).await?;
+            qb.push(", title = ").push_bind(title.clone());
+            qb.push(", slug = ").push_bind(slug);
+        }
+        if let Some(content) = content {
+            qb.push(", content = ").push_bind(content.clone());
+            if let Some(content_html) = &patch.content_html {
+                qb.push(", content_html = ").push_bind(content_html.clone());
+            }
+        }
+        qb.push(" WHERE id = ")
+            .push_bind(id)
+            .push(format!(" RETURNING {POST_COLUMNS}"));
+        let row = qb.build().fetch_one(&mut *tx).await.map_err(|e| {
+            tracing::error!("failed to patch post: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let updated = row_to_post(&row);
+        // Ревизии хранят заголовок и текст, смена одних тегов ревизию не создаёт
+        let revision = if title.is_some() || content.is_some() {
+            Some(insert_revision(&mut tx, &updated, editor_id).await?)
+        } else {
+            None
+        };
+        tx.commit().await.map_err(|e| {
+            tracing::error!("failed to commit post patch: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        tracing::info!(post_id = %updated.id, ?revision, "post patched");
+        Ok(Some(updated))
+    }
+
+    async fn delete(
+        &self,
+        _id: i64,
+        expected_versions: Option<Vec<i32>>,
+    ) -> Result<Option<Post>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE posts
+            SET deleted_at = now(), version = version + 1
+            WHERE id = $1 AND deleted_at IS NULL AND ($2::int[] IS NULL OR version = ANY($2))
+            RETURNING {POST_COLUMNS}
+            "#
+        ))
         .bind(_id)
+        .bind(expected_versions)
         .fetch_optional(&self.pool)
         .await
         .map_err(|e| {
@@ -171,18 +529,816 @@ impl PostRepository for PostgresPostRepository {
             PostError::Internal(format!("database error: {}", e))
         })?;
         if let Some(row) = row {
-            let post = Post {
-                id: row.get("id"),
-                title: row.get("title"),
-                content: row.get("content"),
-                author_id: row.get("author_id"),
-                created_at: row.get("created_at"),
-            };
-            tracing::info!(post_id = %post.id, title = %post.title, "post deleted");
+            let post = row_to_post(&row);
+            tracing::info!(post_id = %post.id, title = %post.title, "post moved to trash");
             Ok(Some(post))
         } else {
             tracing::info!("post {} not found for deletion", _id);
             Ok(None)
         }
     }
+
+    async fn set_status(&self, id: i64, status: PostStatus) -> Result<Option<Post>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE posts
+            SET status = $1,
+                published_at = CASE
+                    WHEN $1 = 'published' THEN COALESCE(published_at, now())
+                    WHEN $1 = 'draft' THEN NULL
+                    ELSE published_at
+                END,
+                publish_at = CASE WHEN $1 = 'draft' THEN publish_at END,
+                version = version + 1
+            WHERE id = $2 AND deleted_at IS NULL
+            RETURNING {POST_COLUMNS}
+            "#
+        ))
+        .bind(status.as_str())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to change post status: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        if let Some(row) = row {
+            let post = row_to_post(&row);
+            tracing::info!(post_id = %post.id, status = %status.as_str(), "post status changed");
+            Ok(Some(post))
+        } else {
+            tracing::info!("post {} not found for status change", id);
+            Ok(None)
+        }
+    }
+
+    async fn set_publish_at(
+        &self,
+        id: i64,
+        publish_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<Post>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE posts
+            SET publish_at = $1, version = version + 1
+            WHERE id = $2 AND status = 'draft' AND deleted_at IS NULL
+            RETURNING {POST_COLUMNS}
+            "#
+        ))
+        .bind(publish_at)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to schedule post: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        if let Some(row) = row {
+            let post = row_to_post(&row);
+            tracing::info!(post_id = %post.id, publish_at = ?post.publish_at, "post scheduled");
+            Ok(Some(post))
+        } else {
+            tracing::info!("draft {} not found for scheduling", id);
+            Ok(None)
+        }
+    }
+
+    async fn set_comment_mode(
+        &self,
+        id: i64,
+        mode: CommentMode,
+    ) -> Result<Option<Post>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE posts
+            SET comment_mode = $1, version = version + 1
+            WHERE id = $2 AND deleted_at IS NULL
+            RETURNING {POST_COLUMNS}
+            "#
+        ))
+        .bind(mode.as_str())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to set comment mode: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(row.as_ref().map(row_to_post))
+    }
+
+    async fn add_reaction(
+        &self,
+        post_id: i64,
+        user_id: i64,
+        kind: ReactionKind,
+    ) -> Result<bool, PostError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO post_reactions (post_id, user_id, kind)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(post_id)
+        .bind(user_id)
+        .bind(kind.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to add reaction: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_reaction(
+        &self,
+        post_id: i64,
+        user_id: i64,
+        kind: ReactionKind,
+    ) -> Result<bool, PostError> {
+        let result = sqlx::query(
+            "DELETE FROM post_reactions WHERE post_id = $1 AND user_id = $2 AND kind = $3",
+        )
+        .bind(post_id)
+        .bind(user_id)
+        .bind(kind.as_str())
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to remove reaction: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_user_reactions(
+        &self,
+        user_id: i64,
+        post_ids: &[i64],
+    ) -> Result<Vec<(i64, ReactionKind)>, PostError> {
+        let rows = sqlx::query(
+            "SELECT post_id, kind FROM post_reactions WHERE user_id = $1 AND post_id = ANY($2)",
+        )
+        .bind(user_id)
+        .bind(post_ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to load user reactions: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let reactions = rows
+            .iter()
+            .filter_map(|row| {
+                let kind = ReactionKind::parse(row.get("kind")).ok()?;
+                Some((row.get("post_id"), kind))
+            })
+            .collect();
+        Ok(reactions)
+    }
+
+    async fn find_collaborator_role(
+        &self,
+        post_id: i64,
+        user_id: i64,
+    ) -> Result<Option<PostRole>, PostError> {
+        let role: Option<String> = sqlx::query_scalar(
+            "SELECT role FROM post_collaborators WHERE post_id = $1 AND user_id = $2",
+        )
+        .bind(post_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to find collaborator role: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(role.and_then(|role| PostRole::parse(&role).ok()))
+    }
+
+    async fn list_collaborators(&self, post_id: i64) -> Result<Vec<Collaborator>, PostError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.author_id AS user_id, u.username, 'owner'::VARCHAR AS role,
+                   NULL::BIGINT AS invited_by, p.created_at, 0 AS rank
+            FROM posts p
+            JOIN users u ON u.id = p.author_id
+            WHERE p.id = $1
+            UNION ALL
+            SELECT c.user_id, u.username, c.role, c.invited_by, c.created_at, 1
+            FROM post_collaborators c
+            JOIN users u ON u.id = c.user_id
+            WHERE c.post_id = $1
+            ORDER BY rank, created_at, user_id
+            "#,
+        )
+        .bind(post_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to list collaborators: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows.iter().filter_map(row_to_collaborator).collect())
+    }
+
+    async fn upsert_collaborator(
+        &self,
+        post_id: i64,
+        user_id: i64,
+        role: PostRole,
+        invited_by: i64,
+    ) -> Result<Option<Collaborator>, PostError> {
+        // Повторное приглашение меняет только роль, автор приглашения остаётся прежним
+        let row = sqlx::query(
+            r#"
+            WITH c AS (
+                INSERT INTO post_collaborators (post_id, user_id, role, invited_by)
+                SELECT $1, id, $3, $4 FROM users WHERE id = $2
+                ON CONFLICT (post_id, user_id) DO UPDATE SET role = EXCLUDED.role
+                RETURNING user_id, role, invited_by, created_at
+            )
+            SELECT c.*, u.username FROM c JOIN users u ON u.id = c.user_id
+            "#,
+        )
+        .bind(post_id)
+        .bind(user_id)
+        .bind(role.as_str())
+        .bind(invited_by)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to save collaborator: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        if row.is_some() {
+            tracing::info!(post_id, user_id, role = role.as_str(), "collaborator saved");
+        }
+        Ok(row.as_ref().and_then(row_to_collaborator))
+    }
+
+    async fn remove_collaborator(&self, post_id: i64, user_id: i64) -> Result<bool, PostError> {
+        let result =
+            sqlx::query("DELETE FROM post_collaborators WHERE post_id = $1 AND user_id = $2")
+                .bind(post_id)
+                .bind(user_id)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| {
+                    tracing::error!("failed to remove collaborator: {}", e);
+                    PostError::Internal(format!("database error: {}", e))
+                })?;
+        if result.rows_affected() > 0 {
+            tracing::info!(post_id, user_id, "collaborator removed");
+        }
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_trash(&self, owner_id: i64) -> Result<Vec<Post>, PostError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {POST_LIST_COLUMNS}
+            FROM posts
+            WHERE deleted_at IS NOT NULL
+              AND (author_id = $1 OR id IN (
+                  SELECT post_id FROM post_collaborators WHERE user_id = $1 AND role = 'owner'
+              ))
+            ORDER BY deleted_at DESC, id DESC
+            "#
+        ))
+        .bind(owner_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to load trash: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows.iter().map(row_to_post).collect())
+    }
+
+    async fn restore(
+        &self,
+        id: i64,
+        owner_id: i64,
+        deleted_after: DateTime<Utc>,
+    ) -> Result<Option<Post>, PostError> {
+        let row = sqlx::query(&format!(
+            r#"
+            UPDATE posts
+            SET deleted_at = NULL, version = version + 1
+            WHERE id = $1 AND deleted_at > $3
+              AND (author_id = $2 OR id IN (
+                  SELECT post_id FROM post_collaborators WHERE user_id = $2 AND role = 'owner'
+              ))
+            RETURNING {POST_COLUMNS}
+            "#
+        ))
+        .bind(id)
+        .bind(owner_id)
+        .bind(deleted_after)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to restore post: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        if let Some(row) = row {
+            let post = row_to_post(&row);
+            tracing::info!(post_id = %post.id, title = %post.title, "post restored from trash");
+            Ok(Some(post))
+        } else {
+            tracing::info!("post {} not found in trash", id);
+            Ok(None)
+        }
+    }
+
+    async fn purge_deleted(
+        &self,
+        deleted_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, PostError> {
+        // Ревизии, теги, комментарии и реакции удаляются каскадно
+        let result = sqlx::query(
+            r#"
+            DELETE FROM posts
+            WHERE id IN (
+                SELECT id
+                FROM posts
+                WHERE deleted_at <= $1
+                ORDER BY deleted_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            "#,
+        )
+        .bind(deleted_before)
+        .bind(limit)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to purge deleted posts: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(result.rows_affected())
+    }
+
+    async fn find_without_html(&self, limit: i64) -> Result<Vec<Post>, PostError> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {POST_LIST_COLUMNS}
+            FROM posts
+            WHERE content_html IS NULL
+            ORDER BY id
+            LIMIT $1
+            "#
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to load posts without html: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows.iter().map(row_to_post).collect())
+    }
+
+    async fn reindex_search(&self) -> Result<u64, PostError> {
+        // search_vector генерируется из search_language, поэтому смена языка пересчитывает его
+        let result = sqlx::query(
+            "UPDATE posts SET search_language = $1::regconfig WHERE search_language <> $1::regconfig",
+        )
+        .bind(&self.search_language)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to reindex posts for search: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(result.rows_affected())
+    }
+
+    async fn backfill_slugs(&self) -> Result<u64, PostError> {
+        // Миграция не могла транслитерировать заголовки в SQL и выдала slug'и по id
+        let ids: Vec<i64> = sqlx::query_scalar("SELECT id FROM posts WHERE slug = 'post-' || id")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to load posts with placeholder slugs: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        let mut updated = 0;
+        for id in ids {
+            let mut tx = self.pool.begin().await.map_err(|e| {
+                tracing::error!("failed to start transaction: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+            // Пост могли переименовать после выборки
+            let current: Option<(String, String)> = sqlx::query_as(
+                "SELECT slug, title FROM posts WHERE id = $1 AND slug = 'post-' || id FOR UPDATE",
+            )
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to lock post for slug backfill: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+            let Some((current_slug, title)) = current else {
+                continue;
+            };
+            // Старый slug уходит в историю, так что прежние ссылки продолжают работать
+            let slug = next_slug(&mut tx, id, current_slug.clone(), &title).await?;
+            if slug == current_slug {
+                continue;
+            }
+            sqlx::query("UPDATE posts SET slug = $1 WHERE id = $2")
+                .bind(&slug)
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| {
+                    tracing::error!("failed to backfill post slug: {}", e);
+                    PostError::Internal(format!("database error: {}", e))
+                })?;
+            tx.commit().await.map_err(|e| {
+                tracing::error!("failed to commit slug backfill: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+            updated += 1;
+        }
+        Ok(updated)
+    }
+
+    async fn set_content_html(&self, id: i64, content_html: &str) -> Result<(), PostError> {
+        // Пост мог быть изменён после выборки, и его свежий HTML затирать нельзя
+        sqlx::query("UPDATE posts SET content_html = $1 WHERE id = $2 AND content_html IS NULL")
+            .bind(content_html)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to save rendered content: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        Ok(())
+    }
+
+    async fn publish_due(&self, limit: i64) -> Result<Vec<Post>, PostError> {
+        // SKIP LOCKED позволяет нескольким инстансам сервера разбирать очередь,
+        // не публикуя один и тот же пост дважды
+        let rows = sqlx::query(&format!(
+            r#"
+            UPDATE posts
+            SET status = 'published',
+                published_at = COALESCE(published_at, publish_at),
+                publish_at = NULL,
+                version = version + 1
+            WHERE id IN (
+                SELECT id
+                FROM posts
+                WHERE status = 'draft' AND publish_at <= now() AND deleted_at IS NULL
+                ORDER BY publish_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING {POST_LIST_COLUMNS}
+            "#
+        ))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to publish scheduled posts: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let posts: Vec<Post> = rows.iter().map(row_to_post).collect();
+        for post in &posts {
+            tracing::info!(post_id = %post.id, title = %post.title, "scheduled post published");
+        }
+        Ok(posts)
+    }
+
+    async fn search(&self, query: &PostSearchQuery) -> Result<Vec<PostSearchHit>, PostError> {
+        let options = format!(
+            "StartSel=\"{}\", StopSel=\"{}\", MaxWords=35, MinWords=15, MaxFragments=2",
+            HIGHLIGHT_START, HIGHLIGHT_STOP
+        );
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {POST_LIST_COLUMNS}, rank,
+                   ts_headline($1::regconfig, title, q, 'HighlightAll=true, ' || $6) AS title_highlight,
+                   ts_headline($1::regconfig, content, q, $6) AS snippet
+            FROM (
+                SELECT p.*, q, ts_rank(p.search_vector, q) AS rank
+                FROM posts p, websearch_to_tsquery($1::regconfig, $2) q
+                WHERE p.search_vector @@ q
+                  AND p.deleted_at IS NULL
+                  AND (p.status = 'published' OR p.author_id = $7
+                       OR p.id IN (SELECT post_id FROM post_collaborators WHERE user_id = $7))
+            ) posts
+            WHERE $3::real IS NULL OR (rank, id) < ($3, $4)
+            ORDER BY rank DESC, id DESC
+            LIMIT $5
+            "#
+        ))
+        .bind(&self.search_language)
+        .bind(&query.text)
+        .bind(query.after.map(|c| c.rank))
+        .bind(query.after.map(|c| c.id))
+        .bind(query.limit)
+        .bind(options)
+        .bind(query.viewer_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to search posts: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let hits: Vec<PostSearchHit> = rows
+            .into_iter()
+            .map(|row| PostSearchHit {
+                post: row_to_post(&row),
+                rank: row.get("rank"),
+                title_highlight: highlight_to_html(row.get("title_highlight")),
+                snippet: highlight_to_html(row.get("snippet")),
+            })
+            .collect();
+        tracing::info!("found {} posts", hits.len());
+        Ok(hits)
+    }
+
+    async fn list_revisions(&self, post_id: i64) -> Result<Vec<PostRevision>, PostError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT post_id, revision, title, content, editor_id, created_at
+            FROM post_revisions
+            WHERE post_id = $1
+            ORDER BY revision DESC
+            "#,
+        )
+        .bind(post_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to fetch revisions: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let revisions: Vec<PostRevision> = rows.iter().map(row_to_revision).collect();
+        tracing::info!(post_id, "fetched {} revisions", revisions.len());
+        Ok(revisions)
+    }
+
+    async fn find_revision(
+        &self,
+        post_id: i64,
+        revision: i32,
+    ) -> Result<Option<PostRevision>, PostError> {
+        let row = sqlx::query(
+            r#"
+            SELECT post_id, revision, title, content, editor_id, created_at
+            FROM post_revisions
+            WHERE post_id = $1 AND revision = $2
+            "#,
+        )
+        .bind(post_id)
+        .bind(revision)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to fetch revision: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(row.as_ref().map(row_to_revision))
+    }
+}
+
+fn push_cursor_value(
+    qb: &mut QueryBuilder<'_, Postgres>,
+    field: PostSortField,
+    cursor: &PostCursor,
+) {
+    match field {
+        PostSortField::CreatedAt => qb.push_bind(cursor.created_at),
+        PostSortField::UpdatedAt => qb.push_bind(cursor.updated_at),
+        PostSortField::PublishedAt => qb.push_bind(cursor.published_at),
+        PostSortField::Title => qb.push_bind(cursor.title.clone()),
+        PostSortField::AuthorId => qb.push_bind(cursor.author_id),
+        PostSortField::Id => qb.push_bind(cursor.id),
+    };
+}
+
+fn ensure_version(id: i64, current: i32, expected: Option<&[i32]>) -> Result<(), PostError> {
+    match expected {
+        Some(expected) if !expected.contains(&current) => Err(PostError::PreconditionFailed(
+            format!("post {} is at version {}, not {:?}", id, current, expected),
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Slug меняется только если заголовок поменялся по существу; старый уходит в историю
+async fn next_slug(
+    tx: &mut Transaction<'_, Postgres>,
+    id: i64,
+    current_slug: String,
+    title: &str,
+) -> Result<String, PostError> {
+    let base = slugify(title);
+    if matches_base(&current_slug, &base) {
+        return Ok(current_slug);
+    }
+    let slug = allocate_slug(tx, &base, Some(id)).await?;
+    move_slug_to_history(tx, id, &current_slug, &slug).await?;
+    Ok(slug)
+}
+
+// Подбирает свободный slug вида base, base-2, base-3...; advisory-блокировка по base
+// сериализует конкурентные вставки с одинаковым заголовком до конца транзакции.
+// Старые slug'и самого поста считаются свободными: пост может вернуться к прежнему заголовку
+async fn allocate_slug(
+    tx: &mut Transaction<'_, Postgres>,
+    base: &str,
+    post_id: Option<i64>,
+) -> Result<String, PostError> {
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+        .bind(base)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to lock slug: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+    let taken: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT slug FROM posts
+        WHERE (slug = $1 OR slug LIKE $1 || '-%') AND id IS DISTINCT FROM $2
+        UNION
+        SELECT slug FROM post_slug_history
+        WHERE (slug = $1 OR slug LIKE $1 || '-%') AND post_id IS DISTINCT FROM $2
+        "#,
+    )
+    .bind(base)
+    .bind(post_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("failed to load taken slugs: {}", e);
+        PostError::Internal(format!("database error: {}", e))
+    })?;
+    if !taken.iter().any(|s| s == base) {
+        return Ok(base.to_string());
+    }
+    let slug = (2..)
+        .map(|n| format!("{}-{}", base, n))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("infinite sequence always yields a free slug");
+    Ok(slug)
+}
+
+async fn move_slug_to_history(
+    tx: &mut Transaction<'_, Postgres>,
+    post_id: i64,
+    old_slug: &str,
+    new_slug: &str,
+) -> Result<(), PostError> {
+    sqlx::query("DELETE FROM post_slug_history WHERE slug = $1")
+        .bind(new_slug)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to reclaim slug: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+    sqlx::query("INSERT INTO post_slug_history (slug, post_id) VALUES ($1, $2)")
+        .bind(old_slug)
+        .bind(post_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to save slug history: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+    Ok(())
+}
+
+// Заменяет набор тегов поста; недостающие теги создаются
+async fn set_post_tags(
+    tx: &mut Transaction<'_, Postgres>,
+    post_id: i64,
+    tags: &[String],
+) -> Result<(), PostError> {
+    sqlx::query("DELETE FROM post_tags WHERE post_id = $1")
+        .bind(post_id)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to clear post tags: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+    if tags.is_empty() {
+        return Ok(());
+    }
+    sqlx::query("INSERT INTO tags (name) SELECT unnest($1::text[]) ON CONFLICT (name) DO NOTHING")
+        .bind(tags)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to create tags: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+    sqlx::query(
+        r#"
+        INSERT INTO post_tags (post_id, tag_id)
+        SELECT $1, id FROM tags WHERE name = ANY($2)
+        "#,
+    )
+    .bind(post_id)
+    .bind(tags)
+    .execute(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("failed to attach tags: {}", e);
+        PostError::Internal(format!("database error: {}", e))
+    })?;
+    Ok(())
+}
+
+fn row_to_post(row: &PgRow) -> Post {
+    let status: String = row.get("status");
+    Post {
+        id: row.get("id"),
+        title: row.get("title"),
+        content: row.get("content"),
+        // NULL — пост ещё не прошёл фоновый рендеринг после миграции
+        content_html: row
+            .get::<Option<String>, _>("content_html")
+            .unwrap_or_default(),
+        author_id: row.get("author_id"),
+        slug: row.get("slug"),
+        tags: row.get("tags"),
+        reactions: summarize_reactions(&row.get::<Json<HashMap<String, i64>>, _>("reactions")),
+        series: row
+            .get::<Option<Json<SeriesPlacement>>, _>("series")
+            .map(|series| series_navigation(series.0)),
+        comment_mode: CommentMode::parse(row.get("comment_mode")).unwrap_or(CommentMode::Open),
+        // CHECK-ограничение в таблице не пропускает неизвестные значения
+        status: PostStatus::parse(&status).unwrap_or(PostStatus::Draft),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+        published_at: row.get("published_at"),
+        publish_at: row.get("publish_at"),
+        deleted_at: row.get("deleted_at"),
+        version: row.get("version"),
+    }
+}
+
+// Сохраняет текущее состояние поста следующей по счёту ревизией
+async fn insert_revision(
+    tx: &mut Transaction<'_, Postgres>,
+    post: &Post,
+    editor_id: i64,
+) -> Result<i32, PostError> {
+    let row = sqlx::query(
+        r#"
+        INSERT INTO post_revisions (post_id, revision, title, content, editor_id)
+        SELECT $1, COALESCE(MAX(revision), 0) + 1, $2, $3, $4
+        FROM post_revisions
+        WHERE post_id = $1
+        RETURNING revision
+        "#,
+    )
+    .bind(post.id)
+    .bind(&post.title)
+    .bind(&post.content)
+    .bind(editor_id)
+    .fetch_one(&mut **tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("failed to save post revision: {}", e);
+        PostError::Internal(format!("database error: {}", e))
+    })?;
+    Ok(row.get("revision"))
+}
+
+fn row_to_collaborator(row: &PgRow) -> Option<Collaborator> {
+    Some(Collaborator {
+        user_id: row.get("user_id"),
+        username: row.get("username"),
+        role: PostRole::parse(row.get("role")).ok()?,
+        invited_by: row.get("invited_by"),
+        created_at: row.get("created_at"),
+    })
+}
+
+fn row_to_revision(row: &PgRow) -> PostRevision {
+    PostRevision {
+        post_id: row.get("post_id"),
+        revision: row.get("revision"),
+        title: row.get("title"),
+        content: row.get("content"),
+        editor_id: row.get("editor_id"),
+        created_at: row.get("created_at"),
+    }
 }
@@ -0,0 +1,282 @@
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing;
+
+use crate::domain::error::PostError;
+use crate::domain::post::PostStatus;
+use crate::domain::series::{NewSeries, Series, SeriesEntry};
+
+#[async_trait]
+pub trait SeriesRepository: Send + Sync {
+    async fn create(&self, series: NewSeries) -> Result<Series, PostError>;
+    async fn find_by_id(&self, id: i64) -> Result<Option<Series>, PostError>;
+    async fn update(
+        &self,
+        id: i64,
+        title: &str,
+        description: &str,
+    ) -> Result<Option<Series>, PostError>;
+    async fn delete(&self, id: i64) -> Result<bool, PostError>;
+    // Оглавление по порядку частей; без include_unpublished — только опубликованные
+    async fn entries(
+        &self,
+        series_id: i64,
+        include_unpublished: bool,
+    ) -> Result<Vec<SeriesEntry>, PostError>;
+    // Заменяет состав серии: посты должны принадлежать owner_id и не входить в другие серии
+    async fn set_posts(
+        &self,
+        series_id: i64,
+        owner_id: i64,
+        post_ids: &[i64],
+    ) -> Result<(), PostError>;
+}
+
+const SERIES_COLUMNS: &str = "id, owner_id, title, description, created_at, updated_at";
+
+#[derive(Clone)]
+pub struct PostgresSeriesRepository {
+    pool: PgPool,
+}
+
+impl PostgresSeriesRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl SeriesRepository for PostgresSeriesRepository {
+    async fn create(&self, series: NewSeries) -> Result<Series, PostError> {
+        let row = sqlx::query(&format!(
+            "INSERT INTO series (owner_id, title, description) VALUES ($1, $2, $3) RETURNING {SERIES_COLUMNS}"
+        ))
+        .bind(series.owner_id)
+        .bind(&series.title)
+        .bind(&series.description)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to create series: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let series = row_to_series(&row);
+        tracing::info!(series_id = %series.id, owner_id = %series.owner_id, "series created");
+        Ok(series)
+    }
+
+    async fn find_by_id(&self, id: i64) -> Result<Option<Series>, PostError> {
+        let row = sqlx::query(&format!(
+            "SELECT {SERIES_COLUMNS} FROM series WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to find series {}: {}", id, e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(row.map(|row| row_to_series(&row)))
+    }
+
+    async fn update(
+        &self,
+        id: i64,
+        title: &str,
+        description: &str,
+    ) -> Result<Option<Series>, PostError> {
+        let row = sqlx::query(&format!(
+            "UPDATE series SET title = $2, description = $3 WHERE id = $1 RETURNING {SERIES_COLUMNS}"
+        ))
+        .bind(id)
+        .bind(title)
+        .bind(description)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to update series {}: {}", id, e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(row.map(|row| row_to_series(&row)))
+    }
+
+    async fn delete(&self, id: i64) -> Result<bool, PostError> {
+        // Посты остаются, удаляется только их принадлежность серии
+        let result = sqlx::query("DELETE FROM series WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to delete series {}: {}", id, e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn entries(
+        &self,
+        series_id: i64,
+        include_unpublished: bool,
+    ) -> Result<Vec<SeriesEntry>, PostError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT sp.position, p.id, p.title, p.slug, p.status, p.published_at
+            FROM series_posts sp
+            JOIN posts p ON p.id = sp.post_id
+            WHERE sp.series_id = $1
+              AND p.deleted_at IS NULL
+              AND ($2 OR p.status = 'published')
+            ORDER BY sp.position
+            "#,
+        )
+        .bind(series_id)
+        .bind(include_unpublished)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to list series posts: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(rows
+            .iter()
+            .map(|row| SeriesEntry {
+                position: row.get("position"),
+                id: row.get("id"),
+                title: row.get("title"),
+                slug: row.get("slug"),
+                status: PostStatus::parse(row.get("status")).unwrap_or(PostStatus::Draft),
+                published_at: row.get("published_at"),
+            })
+            .collect())
+    }
+
+    async fn set_posts(
+        &self,
+        series_id: i64,
+        owner_id: i64,
+        post_ids: &[i64],
+    ) -> Result<(), PostError> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            tracing::error!("failed to start transaction: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let owned: Vec<i64> = sqlx::query_scalar(
+            "SELECT id FROM posts WHERE id = ANY($1) AND author_id = $2 AND deleted_at IS NULL",
+        )
+        .bind(post_ids)
+        .bind(owner_id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to check series posts: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let taken: Option<(i64, i64)> = sqlx::query_as(
+            "SELECT post_id, series_id FROM series_posts WHERE post_id = ANY($1) AND series_id <> $2 LIMIT 1",
+        )
+        .bind(post_ids)
+        .bind(series_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to check series membership: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        check_series_posts(post_ids, &owned, taken)?;
+
+        sqlx::query("DELETE FROM series_posts WHERE series_id = $1")
+            .bind(series_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                tracing::error!("failed to clear series posts: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            })?;
+        sqlx::query(
+            r#"
+            INSERT INTO series_posts (post_id, series_id, position)
+            SELECT post_id, $1, position::INT
+            FROM unnest($2::BIGINT[]) WITH ORDINALITY AS t(post_id, position)
+            "#,
+        )
+        .bind(series_id)
+        .bind(post_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e.as_database_error() {
+            // Пост успели добавить в другую серию после проверки
+            Some(db) if db.is_unique_violation() => {
+                PostError::InvalidState("post already belongs to another series".into())
+            }
+            _ => {
+                tracing::error!("failed to add series posts: {}", e);
+                PostError::Internal(format!("database error: {}", e))
+            }
+        })?;
+
+        tx.commit().await.map_err(|e| {
+            tracing::error!("failed to commit series posts: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        tracing::info!(series_id, posts = post_ids.len(), "series posts updated");
+        Ok(())
+    }
+}
+
+// owned — посты из списка, принадлежащие владельцу серии; taken — пост, уже входящий в другую серию
+pub(crate) fn check_series_posts(
+    post_ids: &[i64],
+    owned: &[i64],
+    taken: Option<(i64, i64)>,
+) -> Result<(), PostError> {
+    if let Some(id) = post_ids.iter().find(|id| !owned.contains(id)) {
+        return Err(PostError::Validation(format!(
+            "post {} does not exist or belongs to another author",
+            id
+        )));
+    }
+    if let Some((post_id, other)) = taken {
+        return Err(PostError::InvalidState(format!(
+            "post {} already belongs to series {}",
+            post_id, other
+        )));
+    }
+    Ok(())
+}
+
+fn row_to_series(row: &PgRow) -> Series {
+    Series {
+        id: row.get("id"),
+        owner_id: row.get("owner_id"),
+        title: row.get("title"),
+        description: row.get("description"),
+        created_at: row.get("created_at"),
+        updated_at: row.get("updated_at"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_foreign_and_taken_posts() {
+        assert!(check_series_posts(&[], &[], None).is_ok());
+        assert!(check_series_posts(&[2, 1], &[1, 2], None).is_ok());
+        // Чужой или несуществующий пост не попадает в выборку владельца
+        assert!(matches!(
+            check_series_posts(&[1, 3], &[1], None),
+            Err(PostError::Validation(message)) if message.contains("post 3")
+        ));
+        assert!(matches!(
+            check_series_posts(&[1, 2], &[1, 2], Some((2, 7))),
+            Err(PostError::InvalidState(message)) if message.contains("series 7")
+        ));
+        // Сначала проверяется владение, потом занятость
+        assert!(matches!(
+            check_series_posts(&[1, 3], &[1], Some((1, 7))),
+            Err(PostError::Validation(_))
+        ));
+    }
+}
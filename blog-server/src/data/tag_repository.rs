@@ -0,0 +1,53 @@
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use tracing;
+
+use crate::domain::{error::PostError, tag::TagCount};
+
+#[async_trait]
+pub trait TagRepository: Send + Sync {
+    async fn list_with_counts(&self) -> Result<Vec<TagCount>, PostError>;
+}
+
+#[derive(Clone)]
+pub struct PostgresTagRepository {
+    pool: PgPool,
+}
+
+impl PostgresTagRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TagRepository for PostgresTagRepository {
+    async fn list_with_counts(&self) -> Result<Vec<TagCount>, PostError> {
+        // Теги без опубликованных постов не показываем
+        let rows = sqlx::query(
+            r#"
+            SELECT t.name, COUNT(*) AS post_count
+            FROM tags t
+            JOIN post_tags pt ON pt.tag_id = t.id
+            JOIN posts p ON p.id = pt.post_id
+            WHERE p.status = 'published' AND p.deleted_at IS NULL
+            GROUP BY t.name
+            ORDER BY post_count DESC, t.name
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to list tags: {}", e);
+            PostError::Internal(format!("database error: {}", e))
+        })?;
+        let tags = rows
+            .iter()
+            .map(|row| TagCount {
+                name: row.get("name"),
+                post_count: row.get("post_count"),
+            })
+            .collect();
+        Ok(tags)
+    }
+}
@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use sqlx::{PgPool, Row};
 use tracing;
 
+use crate::domain::follow::{FollowCursor, FollowUser};
 use crate::domain::{error::AuthError, user::NewUser, user::User};
 
 #[async_trait]
@@ -9,6 +10,21 @@ pub trait UserRepository: Send + Sync {
     async fn create(&self, user: NewUser) -> Result<User, AuthError>;
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, AuthError>;
     async fn find_by_id(&self, id: i64) -> Result<Option<User>, AuthError>;
+    // false, если подписка уже была (или её не было при отписке)
+    async fn follow(&self, follower_id: i64, followee_id: i64) -> Result<bool, AuthError>;
+    async fn unfollow(&self, follower_id: i64, followee_id: i64) -> Result<bool, AuthError>;
+    async fn find_followers(
+        &self,
+        user_id: i64,
+        after: Option<&FollowCursor>,
+        limit: i64,
+    ) -> Result<Vec<FollowUser>, AuthError>;
+    async fn find_following(
+        &self,
+        user_id: i64,
+        after: Option<&FollowCursor>,
+        limit: i64,
+    ) -> Result<Vec<FollowUser>, AuthError>;
 }
 
 #[derive(Clone)]
@@ -29,7 +45,7 @@ impl UserRepository for PostgresUserRepository {
             r#"
             INSERT INTO users (email, username, password_hash)
             VALUES ($1, $2, $3)
-            RETURNING id, email, username, password_hash, created_at
+            RETURNING id, email, username, password_hash, created_at, updated_at
             "#,
         )
         .bind(&user.email)
@@ -54,7 +70,8 @@ impl UserRepository for PostgresUserRepository {
             email: user.email,
             username: user.username,
             password_hash: user.password_hash,
-            created_at: chrono::Utc::now(),
+            created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
         };
         tracing::info!(user_id = %user_dto.id, email = %user_dto.email, "user created");
         Ok(user_dto)
@@ -63,7 +80,7 @@ impl UserRepository for PostgresUserRepository {
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, AuthError> {
         let row = sqlx::query(
             r#"
-            SELECT id, email, username, password_hash, created_at
+            SELECT id, email, username, password_hash, created_at, updated_at
             FROM users
             WHERE email = $1
             "#,
@@ -82,13 +99,14 @@ impl UserRepository for PostgresUserRepository {
             username: row.get("username"),
             password_hash: row.get("password_hash"),
             created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
         }))
     }
 
     async fn find_by_id(&self, id: i64) -> Result<Option<User>, AuthError> {
         let row = sqlx::query(
             r#"
-            SELECT id, email, username, password_hash, created_at
+            SELECT id, email, username, password_hash, created_at, updated_at
             FROM users
             WHERE id = $1
             "#,
@@ -107,6 +125,118 @@ impl UserRepository for PostgresUserRepository {
             username: row.get("username"),
             password_hash: row.get("password_hash"),
             created_at: row.get("created_at"),
+            updated_at: row.get("updated_at"),
         }))
     }
+
+    async fn follow(&self, follower_id: i64, followee_id: i64) -> Result<bool, AuthError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO follows (follower_id, followee_id)
+            VALUES ($1, $2)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(follower_id)
+        .bind(followee_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to follow user {}: {}", followee_id, e);
+            AuthError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn unfollow(&self, follower_id: i64, followee_id: i64) -> Result<bool, AuthError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM follows
+            WHERE follower_id = $1 AND followee_id = $2
+            "#,
+        )
+        .bind(follower_id)
+        .bind(followee_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("failed to unfollow user {}: {}", followee_id, e);
+            AuthError::Internal(format!("database error: {}", e))
+        })?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_followers(
+        &self,
+        user_id: i64,
+        after: Option<&FollowCursor>,
+        limit: i64,
+    ) -> Result<Vec<FollowUser>, AuthError> {
+        find_follow_page(
+            &self.pool,
+            "followee_id",
+            "follower_id",
+            user_id,
+            after,
+            limit,
+        )
+        .await
+    }
+
+    async fn find_following(
+        &self,
+        user_id: i64,
+        after: Option<&FollowCursor>,
+        limit: i64,
+    ) -> Result<Vec<FollowUser>, AuthError> {
+        find_follow_page(
+            &self.pool,
+            "follower_id",
+            "followee_id",
+            user_id,
+            after,
+            limit,
+        )
+        .await
+    }
+}
+
+// Страница связей из follows: key — сторона, по которой фильтруем, other — пользователи в ответе
+async fn find_follow_page(
+    pool: &PgPool,
+    key: &str,
+    other: &str,
+    user_id: i64,
+    after: Option<&FollowCursor>,
+    limit: i64,
+) -> Result<Vec<FollowUser>, AuthError> {
+    let rows = sqlx::query(&format!(
+        r#"
+        SELECT u.id, u.username, f.created_at AS followed_at
+        FROM follows f
+        JOIN users u ON u.id = f.{other}
+        WHERE f.{key} = $1
+          AND ($2::timestamptz IS NULL OR (f.created_at, f.{other}) < ($2, $3))
+        ORDER BY f.created_at DESC, f.{other} DESC
+        LIMIT $4
+        "#
+    ))
+    .bind(user_id)
+    .bind(after.map(|c| c.followed_at))
+    .bind(after.map(|c| c.user_id).unwrap_or_default())
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("failed to fetch follows of user {}: {}", user_id, e);
+        AuthError::Internal(format!("database error: {}", e))
+    })?;
+    Ok(rows
+        .into_iter()
+        .map(|row| FollowUser {
+            id: row.get("id"),
+            username: row.get("username"),
+            followed_at: row.get("followed_at"),
+        })
+        .collect())
 }
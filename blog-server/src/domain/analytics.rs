@@ -0,0 +1,158 @@
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::domain::error::DomainError;
+
+pub const DEFAULT_STATS_DAYS: i64 = 30;
+pub const MAX_STATS_DAYS: i64 = 366;
+
+// Фрагменты User-Agent краулеров, превью ссылок и HTTP-библиотек, в нижнем регистре
+const BOT_MARKERS: &[&str] = &[
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "scrap",
+    "headless",
+    "lighthouse",
+    "preview",
+    "facebookexternalhit",
+    "embedly",
+    "feed",
+    "monitor",
+    "curl",
+    "wget",
+    "python",
+    "go-http-client",
+    "java/",
+    "okhttp",
+    "axios",
+    "node-fetch",
+    "libwww",
+    "httpclient",
+];
+
+// Запрос без User-Agent тоже считается ботом: браузеры его всегда присылают
+pub fn is_bot(user_agent: Option<&str>) -> bool {
+    let Some(user_agent) = user_agent.map(str::trim).filter(|ua| !ua.is_empty()) else {
+        return true;
+    };
+    let user_agent = user_agent.to_ascii_lowercase();
+    BOT_MARKERS.iter().any(|marker| user_agent.contains(marker))
+}
+
+// Просмотр, накопленный в памяти до записи в базу
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ViewKey {
+    pub post_id: i64,
+    pub day: NaiveDate,
+    pub visitor_hash: String,
+}
+
+// visitors за период — сумма уникальных посетителей по дням
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ViewTotals {
+    pub views: i64,
+    pub visitors: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DailyViews {
+    pub date: NaiveDate,
+    pub views: i64,
+    pub visitors: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PostStats {
+    pub post_id: i64,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub totals: ViewTotals,
+    pub all_time: ViewTotals,
+    pub daily: Vec<DailyViews>,
+}
+
+// Период включает обе границы; по умолчанию — последние DEFAULT_STATS_DAYS дней
+pub fn stats_range(
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    today: NaiveDate,
+) -> Result<(NaiveDate, NaiveDate), DomainError> {
+    let to = to.unwrap_or(today);
+    let from = from.unwrap_or(to - Duration::days(DEFAULT_STATS_DAYS - 1));
+    if from > to {
+        return Err(DomainError::Validation(
+            "from must not be later than to".into(),
+        ));
+    }
+    if (to - from).num_days() >= MAX_STATS_DAYS {
+        return Err(DomainError::Validation(format!(
+            "period is too long (max {} days)",
+            MAX_STATS_DAYS
+        )));
+    }
+    Ok((from, to))
+}
+
+// Дни без просмотров заполняются нулями, чтобы ряд был непрерывным
+pub fn fill_daily(from: NaiveDate, to: NaiveDate, rows: Vec<DailyViews>) -> Vec<DailyViews> {
+    let mut rows = rows.into_iter().peekable();
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| match rows.next_if(|row| row.date == date) {
+            Some(row) => row,
+            None => DailyViews {
+                date,
+                views: 0,
+                visitors: 0,
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn filters_bots_by_user_agent() {
+        assert!(is_bot(None));
+        assert!(is_bot(Some("  ")));
+        assert!(is_bot(Some(
+            "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)"
+        )));
+        assert!(is_bot(Some("curl/8.5.0")));
+        assert!(!is_bot(Some(
+            "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0"
+        )));
+    }
+
+    #[test]
+    fn fills_missing_days_with_zeros() {
+        let today = date("2026-10-17");
+        assert_eq!(
+            stats_range(None, None, today).unwrap(),
+            (date("2026-09-18"), today)
+        );
+        assert!(stats_range(Some(today), Some(date("2026-10-16")), today).is_err());
+        assert!(stats_range(Some(date("2025-01-01")), None, today).is_err());
+
+        let daily = fill_daily(
+            date("2026-10-15"),
+            today,
+            vec![DailyViews {
+                date: date("2026-10-16"),
+                views: 5,
+                visitors: 3,
+            }],
+        );
+        let views: Vec<i64> = daily.iter().map(|day| day.views).collect();
+        assert_eq!(views, vec![0, 5, 0]);
+        assert_eq!(daily[2].date, today);
+    }
+}
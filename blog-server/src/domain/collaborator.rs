@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+// Роль пользователя в посте; автор поста всегда владелец
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PostRole {
+    Owner,
+    Editor,
+    Viewer,
+}
+
+// Что пользователь собирается сделать с постом
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Permission {
+    // Черновики, ревизии и список соавторов
+    View,
+    // Правка текста и тегов, модерация комментариев
+    Edit,
+    // Публикация, удаление, настройки и управление соавторами
+    Manage,
+}
+
+impl PostRole {
+    pub const ALL: [PostRole; 3] = [Self::Owner, Self::Editor, Self::Viewer];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Editor => "editor",
+            Self::Viewer => "viewer",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, DomainError> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.as_str() == value)
+            .ok_or_else(|| DomainError::Validation(format!("unknown role: {}", value)))
+    }
+
+    pub fn allows(&self, permission: Permission) -> bool {
+        match permission {
+            Permission::View => true,
+            Permission::Edit => matches!(self, Self::Owner | Self::Editor),
+            Permission::Manage => *self == Self::Owner,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Collaborator {
+    pub user_id: i64,
+    pub username: String,
+    pub role: PostRole,
+    // Для автора поста пусто
+    pub invited_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_grant_nested_permissions() {
+        assert!(PostRole::Owner.allows(Permission::Manage));
+        assert!(!PostRole::Editor.allows(Permission::Manage));
+        assert!(PostRole::Editor.allows(Permission::Edit));
+        assert!(!PostRole::Viewer.allows(Permission::Edit));
+        assert!(PostRole::Viewer.allows(Permission::View));
+        assert_eq!(PostRole::parse("editor").unwrap(), PostRole::Editor);
+        assert!(PostRole::parse("admin").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+// Корневой комментарий имеет глубину 0, ответ на ответ — 2 и т.д.
+pub const MAX_COMMENT_DEPTH: i32 = 5;
+pub const MAX_COMMENT_LEN: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Spam,
+}
+
+impl CommentStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::Spam => "spam",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, DomainError> {
+        match value {
+            "pending" => Ok(Self::Pending),
+            "approved" => Ok(Self::Approved),
+            "rejected" => Ok(Self::Rejected),
+            "spam" => Ok(Self::Spam),
+            other => Err(DomainError::Validation(format!(
+                "unknown comment status: {}",
+                other
+            ))),
+        }
+    }
+
+    // Решение модератора можно пересмотреть: одобренный снять, отклонённый или спам одобрить.
+    // В pending комментарий возвращается только правкой текста
+    pub fn can_transition_to(&self, next: CommentStatus) -> bool {
+        matches!(
+            (self, next),
+            (Self::Pending, Self::Approved)
+                | (Self::Pending, Self::Rejected)
+                | (Self::Pending, Self::Spam)
+                | (Self::Approved, Self::Rejected)
+                | (Self::Approved, Self::Spam)
+                | (Self::Rejected, Self::Approved)
+                | (Self::Spam, Self::Approved)
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Comment {
+    pub id: i64,
+    pub post_id: i64,
+    pub author_id: i64,
+    pub parent_id: Option<i64>,
+    pub depth: i32,
+    // У удалённого комментария текст стирается, но узел остаётся, чтобы не рвать ветку ответов
+    pub content: String,
+    pub deleted: bool,
+    pub status: CommentStatus,
+    // Пояснение модератора к последнему решению
+    pub moderation_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    // Неодобренный комментарий видит только его автор; остальным он показывается как удалённый
+    pub fn redact_for(mut self, viewer_id: Option<i64>) -> Self {
+        if self.status != CommentStatus::Approved && viewer_id != Some(self.author_id) {
+            self.content.clear();
+            self.moderation_reason = None;
+            self.deleted = true;
+        }
+        self
+    }
+}
+
+pub struct NewComment {
+    pub post_id: i64,
+    pub author_id: i64,
+    pub parent_id: Option<i64>,
+    pub depth: i32,
+    pub content: String,
+    pub status: CommentStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommentThread {
+    #[serde(flatten)]
+    pub comment: Comment,
+    pub replies: Vec<CommentThread>,
+}
+
+pub fn validate_comment_content(content: &str) -> Result<(), DomainError> {
+    if content.trim().is_empty() {
+        return Err(DomainError::Validation("comment cannot be empty".into()));
+    }
+    if content.chars().count() > MAX_COMMENT_LEN {
+        return Err(DomainError::Validation(format!(
+            "comment is too long (max {} characters)",
+            MAX_COMMENT_LEN
+        )));
+    }
+    Ok(())
+}
+
+// Собирает дерево из плоского списка комментариев поста (в порядке создания).
+// Удалённые комментарии без живых ответов в дерево не попадают
+pub fn build_comment_tree(comments: Vec<Comment>) -> Vec<CommentThread> {
+    let mut children: HashMap<Option<i64>, Vec<Comment>> = HashMap::new();
+    for comment in comments {
+        children.entry(comment.parent_id).or_default().push(comment);
+    }
+    collect_replies(None, &mut children)
+}
+
+fn collect_replies(
+    parent_id: Option<i64>,
+    children: &mut HashMap<Option<i64>, Vec<Comment>>,
+) -> Vec<CommentThread> {
+    let Some(comments) = children.remove(&parent_id) else {
+        return Vec::new();
+    };
+    comments
+        .into_iter()
+        .filter_map(|comment| {
+            let replies = collect_replies(Some(comment.id), children);
+            if comment.deleted && replies.is_empty() {
+                None
+            } else {
+                Some(CommentThread { comment, replies })
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: i64, parent_id: Option<i64>, deleted: bool) -> Comment {
+        Comment {
+            id,
+            post_id: 1,
+            author_id: 1,
+            parent_id,
+            depth: 0,
+            content: String::new(),
+            deleted,
+            status: CommentStatus::Approved,
+            moderation_reason: None,
+            created_at: Utc::now(),
+            edited_at: None,
+        }
+    }
+
+    #[test]
+    fn tree_keeps_deleted_nodes_only_with_live_replies() {
+        let tree = build_comment_tree(vec![
+            comment(1, None, true),
+            comment(2, Some(1), false),
+            comment(3, None, true),
+            comment(4, Some(3), true),
+            comment(5, None, false),
+        ]);
+        let ids: Vec<i64> = tree.iter().map(|t| t.comment.id).collect();
+        assert_eq!(ids, vec![1, 5]);
+        assert_eq!(tree[0].replies[0].comment.id, 2);
+    }
+}
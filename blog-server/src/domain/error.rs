@@ -96,6 +96,17 @@ pub enum PostError {
     Unauthorized,
     #[error("forbidden")]
     Forbidden,
+    #[error("invalid state: {0}")]
+    InvalidState(String),
+    // Пост изменился с тех пор, как клиент его прочитал (If-Match / expected_version)
+    #[error("precondition failed: {0}")]
+    PreconditionFailed(String),
+    #[error("precondition required: {0}")]
+    PreconditionRequired(String),
+    #[error("unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+    #[error("payload too large: {0}")]
+    PayloadTooLarge(String),
     #[error("internal server error: {0}")]
     Internal(String),
 }
@@ -107,6 +118,11 @@ impl ResponseError for PostError {
             PostError::PostNotFound(_) => StatusCode::NOT_FOUND,
             PostError::Unauthorized => StatusCode::UNAUTHORIZED,
             PostError::Forbidden => StatusCode::FORBIDDEN,
+            PostError::InvalidState(_) => StatusCode::CONFLICT,
+            PostError::PreconditionFailed(_) => StatusCode::PRECONDITION_FAILED,
+            PostError::PreconditionRequired(_) => StatusCode::PRECONDITION_REQUIRED,
+            PostError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            PostError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
             PostError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -118,6 +134,11 @@ impl ResponseError for PostError {
             PostError::PostNotFound(resource) => Some(json!({ "resource": resource })),
             PostError::Unauthorized => None,
             PostError::Forbidden => None,
+            PostError::InvalidState(msg) => Some(json!({ "message": msg })),
+            PostError::PreconditionFailed(msg) => Some(json!({ "message": msg })),
+            PostError::PreconditionRequired(msg) => Some(json!({ "message": msg })),
+            PostError::UnsupportedMediaType(msg) => Some(json!({ "message": msg })),
+            PostError::PayloadTooLarge(msg) => Some(json!({ "message": msg })),
             PostError::Internal(_) => None,
         };
         let body = ErrorBody {
@@ -0,0 +1,50 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+// Пользователь в списке подписок или подписчиков
+#[derive(Debug, Clone, Serialize)]
+pub struct FollowUser {
+    pub id: i64,
+    pub username: String,
+    pub followed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FollowPage {
+    pub users: Vec<FollowUser>,
+    pub next_cursor: Option<String>,
+}
+
+// Позиция в списке подписок: время подписки и id последнего отданного пользователя
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FollowCursor {
+    #[serde(rename = "f")]
+    pub followed_at: DateTime<Utc>,
+    #[serde(rename = "i")]
+    pub user_id: i64,
+}
+
+impl FollowCursor {
+    pub fn new(user: &FollowUser) -> Self {
+        Self {
+            followed_at: user.followed_at,
+            user_id: user.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let raw = serde_json::to_vec(self).expect("cursor is always serializable");
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(token: &str) -> Result<Self, DomainError> {
+        let raw = URL_SAFE_NO_PAD
+            .decode(token)
+            .map_err(|_| DomainError::Validation("invalid cursor".into()))?;
+        serde_json::from_slice(&raw).map_err(|_| DomainError::Validation("invalid cursor".into()))
+    }
+}
@@ -0,0 +1,164 @@
+use std::collections::HashSet;
+
+use ammonia::Builder;
+use once_cell::sync::Lazy;
+use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd, html};
+use syntect::html::{ClassStyle, ClassedHTMLGenerator};
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+// Подсветка отдаётся CSS-классами с этим префиксом, тему выбирает фронтенд
+const HIGHLIGHT_CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+static SYNTAXES: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+
+// Белый список поверх настроек ammonia по умолчанию: классы подсветки,
+// якоря сносок и выравнивание колонок таблиц
+static SANITIZER: Lazy<Builder<'static>> = Lazy::new(|| {
+    let mut builder = Builder::default();
+    builder
+        .add_tag_attributes("span", &["class"])
+        .add_tag_attributes("code", &["class"])
+        .add_tag_attributes("pre", &["class"])
+        .add_tag_attributes("sup", &["class"])
+        .add_tag_attributes("div", &["class", "id"])
+        .add_tag_attributes("th", &["style"])
+        .add_tag_attributes("td", &["style"])
+        .filter_style_properties(HashSet::from(["text-align"]));
+    builder
+});
+
+// CommonMark + GFM-таблицы, сноски и зачёркивание; результат безопасно вставлять в страницу
+pub fn render_markdown(content: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_FOOTNOTES | Options::ENABLE_STRIKETHROUGH;
+    let mut events = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+    for event in Parser::new_ext(content, options) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(info) => info
+                        .split(|c: char| c == ',' || c.is_whitespace())
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((lang, String::new()));
+            }
+            Event::Text(text) if code_block.is_some() => {
+                if let Some((_, code)) = code_block.as_mut() {
+                    code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some((lang, code)) = code_block.take() {
+                    events.push(Event::Html(highlight_code(&lang, &code).into()));
+                }
+            }
+            event => events.push(event),
+        }
+    }
+    let mut raw = String::with_capacity(content.len() * 3 / 2);
+    html::push_html(&mut raw, events.into_iter());
+    SANITIZER.clean(&raw).to_string()
+}
+
+// Текст поста без разметки и HTML, одной строкой; используется для анонсов в лентах
+pub fn plain_text(content: &str) -> String {
+    let mut text = String::with_capacity(content.len());
+    for event in Parser::new_ext(
+        content,
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+    ) {
+        match event {
+            Event::Text(chunk) | Event::Code(chunk) => text.push_str(&chunk),
+            Event::SoftBreak | Event::HardBreak | Event::End(_) => text.push(' '),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn highlight_code(lang: &str, code: &str) -> String {
+    let syntax = Some(lang)
+        .filter(|l| !l.is_empty())
+        .and_then(|l| SYNTAXES.find_syntax_by_token(l));
+    let body = match syntax {
+        Some(syntax) => {
+            let mut generator = ClassedHTMLGenerator::new_with_class_style(
+                syntax,
+                &SYNTAXES,
+                HIGHLIGHT_CLASS_STYLE,
+            );
+            let highlighted = LinesWithEndings::from(code)
+                .try_for_each(|line| generator.parse_html_for_line_which_includes_newline(line));
+            match highlighted {
+                Ok(()) => generator.finalize(),
+                Err(e) => {
+                    tracing::warn!("failed to highlight {} code block: {}", lang, e);
+                    escape_html(code)
+                }
+            }
+        }
+        None => escape_html(code),
+    };
+    if lang.is_empty() {
+        format!("<pre><code>{}</code></pre>\n", body)
+    } else {
+        format!(
+            "<pre class=\"highlight\"><code class=\"language-{}\">{}</code></pre>\n",
+            escape_html(lang),
+            body
+        )
+    }
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_strips_scripts_and_dangerous_links() {
+        let html = render_markdown(
+            "<script>alert(1)</script>\n\n[x](javascript:alert(1)) <img src=x onerror=alert(1)>",
+        );
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("onerror"));
+    }
+
+    #[test]
+    fn render_supports_tables_footnotes_and_highlighting() {
+        let html = render_markdown(
+            "| a | b |\n|:-:|---|\n| 1 | 2 |\n\nText[^1]\n\n[^1]: note\n\n```rust\nfn main() {}\n```\n",
+        );
+        assert!(html.contains("<table>"));
+        assert!(html.contains("text-align"));
+        assert!(html.contains("footnote-definition"));
+        assert!(html.contains("class=\"language-rust\""));
+        assert!(html.contains("hl-"));
+    }
+
+    #[test]
+    fn plain_text_drops_markup() {
+        let text = plain_text("# Title\n\nSome **bold** and `code`.\n\n- one\n- two\n\n<b>raw</b>");
+        assert_eq!(text, "Title Some bold and code. one two raw");
+    }
+}
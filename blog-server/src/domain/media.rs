@@ -0,0 +1,238 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+// Состояние фоновой обработки: построение уменьшенных копий
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaStatus {
+    Processing,
+    Ready,
+    Failed,
+}
+
+impl MediaStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Processing => "processing",
+            Self::Ready => "ready",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, DomainError> {
+        match value {
+            "processing" => Ok(Self::Processing),
+            "ready" => Ok(Self::Ready),
+            "failed" => Ok(Self::Failed),
+            other => Err(DomainError::Validation(format!(
+                "unknown media status: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Media {
+    pub id: i64,
+    pub owner_id: i64,
+    // Имя объекта в хранилище
+    pub key: String,
+    pub mime_type: String,
+    pub size: i64,
+    // SHA-256 содержимого в hex
+    pub checksum: String,
+    // Размеры в пикселях с учётом ориентации; известны после обработки
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub status: MediaStatus,
+    pub created_at: DateTime<Utc>,
+}
+
+// Уменьшенная копия картинки в одном из форматов
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaVariant {
+    #[serde(skip)]
+    pub media_id: i64,
+    pub name: String,
+    pub mime_type: String,
+    pub key: String,
+    pub width: i32,
+    pub height: i32,
+    pub size: i64,
+    pub checksum: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadedVariant {
+    #[serde(flatten)]
+    pub variant: MediaVariant,
+    pub url: String,
+}
+
+// Файл вместе с адресом, который можно вставить в текст поста
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadedMedia {
+    #[serde(flatten)]
+    pub media: Media,
+    pub url: String,
+    // Готовая Markdown-вставка картинки
+    pub markdown: String,
+    pub variants: Vec<UploadedVariant>,
+    // Значения атрибута srcset: в формате оригинала и в WebP; null, пока файл обрабатывается
+    pub srcset: Option<String>,
+    pub srcset_webp: Option<String>,
+}
+
+// Всё, что нужно для отдачи файла или его копии по ключу
+pub struct MediaObject {
+    pub mime_type: String,
+    pub checksum: String,
+}
+
+// Целевая ширина уменьшенной копии; больше оригинала картинка не растягивается
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DerivativeSize {
+    pub name: String,
+    pub width: u32,
+}
+
+pub struct NewMedia {
+    pub owner_id: i64,
+    pub key: String,
+    pub mime_type: String,
+    pub size: i64,
+    pub checksum: String,
+}
+
+// Тип файла определяется по первым байтам, а не по заголовку Content-Type или расширению
+pub fn sniff_mime(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if data.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+// Формат "thumbnail=320,medium=800,large=1600"; имя попадает в ключ файла
+pub fn parse_derivative_sizes(value: &str) -> Result<Vec<DerivativeSize>, DomainError> {
+    let invalid = || DomainError::Validation(format!("invalid derivative sizes: {}", value));
+    let mut sizes: Vec<DerivativeSize> = Vec::new();
+    for item in value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+    {
+        let (name, width) = item.split_once('=').ok_or_else(invalid)?;
+        let name = name.trim();
+        let width: u32 = width.trim().parse().map_err(|_| invalid())?;
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+            || width == 0
+            || sizes.iter().any(|size| size.name == name)
+        {
+            return Err(invalid());
+        }
+        sizes.push(DerivativeSize {
+            name: name.to_string(),
+            width,
+        });
+    }
+    Ok(sizes)
+}
+
+// Ключ копии рядом с ключом оригинала: "<uuid>-medium.webp"
+pub fn variant_key(media_key: &str, name: &str, mime_type: &str) -> String {
+    let stem = media_key
+        .split_once('.')
+        .map_or(media_key, |(stem, _)| stem);
+    format!("{}-{}.{}", stem, name, extension_for(mime_type))
+}
+
+// Кандидаты srcset по возрастанию ширины; одинаковые ширины не повторяются
+pub fn srcset(candidates: impl IntoIterator<Item = (String, i32)>) -> Option<String> {
+    let mut candidates: Vec<_> = candidates.into_iter().collect();
+    candidates.sort_by_key(|(_, width)| *width);
+    candidates.dedup_by_key(|(_, width)| *width);
+    if candidates.is_empty() {
+        return None;
+    }
+    Some(
+        candidates
+            .iter()
+            .map(|(url, width)| format!("{} {}w", url, width))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+pub fn extension_for(mime_type: &str) -> &'static str {
+    match mime_type {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        _ => "bin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_by_magic_bytes() {
+        assert_eq!(
+            sniff_mime(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some("image/png")
+        );
+        assert_eq!(
+            sniff_mime(b"\xff\xd8\xff\xe0\0\x10JFIF"),
+            Some("image/jpeg")
+        );
+        assert_eq!(sniff_mime(b"GIF89a\x01\0\x01\0"), Some("image/gif"));
+        assert_eq!(sniff_mime(b"RIFF\x24\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff_mime(b"RIFF\x24\0\0\0WAVEfmt "), None);
+        assert_eq!(
+            sniff_mime(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+            None
+        );
+        assert_eq!(sniff_mime(b""), None);
+    }
+
+    #[test]
+    fn parses_sizes_and_builds_srcset() {
+        let sizes = parse_derivative_sizes("thumbnail=320, medium=800,large=1600").unwrap();
+        assert_eq!(sizes.len(), 3);
+        assert_eq!(sizes[1].name, "medium");
+        assert_eq!(sizes[1].width, 800);
+        assert!(parse_derivative_sizes("").unwrap().is_empty());
+        assert!(parse_derivative_sizes("thumb=0").is_err());
+        assert!(parse_derivative_sizes("a/b=10").is_err());
+        assert!(parse_derivative_sizes("a=10,a=20").is_err());
+
+        assert_eq!(
+            variant_key("abc.jpg", "medium", "image/webp"),
+            "abc-medium.webp"
+        );
+        assert_eq!(
+            srcset(vec![
+                ("/l".to_string(), 1200),
+                ("/t".to_string(), 320),
+                ("/o".to_string(), 1200),
+            ]),
+            Some("/t 320w, /l 1200w".to_string())
+        );
+        assert_eq!(srcset(Vec::new()), None);
+    }
+}
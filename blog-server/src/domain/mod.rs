@@ -1,3 +1,16 @@
 pub mod error;
 pub mod user;
 pub mod post;
+pub mod post_query;
+pub mod post_search;
+pub mod revision;
+pub mod slug;
+pub mod markdown;
+pub mod tag;
+pub mod comment;
+pub mod reaction;
+pub mod follow;
+pub mod media;
+pub mod series;
+pub mod collaborator;
+pub mod analytics;
\ No newline at end of file
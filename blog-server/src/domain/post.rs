@@ -1,19 +1,144 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::domain::error::DomainError;
+use crate::domain::reaction::ReactionCount;
+use crate::domain::series::SeriesNavigation;
+
+// Ограничение колонки posts.title
+pub const MAX_TITLE_LEN: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PostStatus {
+    Draft,
+    Published,
+    Archived,
+}
+
+impl PostStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Draft => "draft",
+            Self::Published => "published",
+            Self::Archived => "archived",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, DomainError> {
+        match value {
+            "draft" => Ok(Self::Draft),
+            "published" => Ok(Self::Published),
+            "archived" => Ok(Self::Archived),
+            other => Err(DomainError::Validation(format!(
+                "unknown post status: {}",
+                other
+            ))),
+        }
+    }
+
+    // Допустимые переходы: draft <-> published, любой из них -> archived, archived -> published
+    pub fn can_transition_to(&self, next: PostStatus) -> bool {
+        matches!(
+            (self, next),
+            (Self::Draft, Self::Published)
+                | (Self::Published, Self::Draft)
+                | (Self::Draft, Self::Archived)
+                | (Self::Published, Self::Archived)
+                | (Self::Archived, Self::Published)
+        )
+    }
+}
+
+// Режим комментирования поста: open — сразу видны, moderated — после одобрения автором
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommentMode {
+    Open,
+    Moderated,
+    Closed,
+}
+
+impl CommentMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Moderated => "moderated",
+            Self::Closed => "closed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, DomainError> {
+        match value {
+            "open" => Ok(Self::Open),
+            "moderated" => Ok(Self::Moderated),
+            "closed" => Ok(Self::Closed),
+            other => Err(DomainError::Validation(format!(
+                "unknown comment mode: {}",
+                other
+            ))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Post {
     pub id: i64,
     pub title: String,
     pub content: String,
+    // Отрендеренный и очищенный от XSS Markdown из content
+    pub content_html: String,
     pub author_id: i64,
+    // Постоянная ссылка на пост, уникальна среди всех постов
+    pub slug: String,
+    pub tags: Vec<String>,
+    pub reactions: Vec<ReactionCount>,
+    // Серия, в которую входит пост, с соседними частями
+    pub series: Option<SeriesNavigation>,
+    pub comment_mode: CommentMode,
+    pub status: PostStatus,
     pub created_at: DateTime<Utc>,
+    // Последнее изменение строки поста, выставляется триггером в БД
+    pub updated_at: DateTime<Utc>,
+    pub published_at: Option<DateTime<Utc>>,
+    // Время отложенной публикации черновика
+    pub publish_at: Option<DateTime<Utc>>,
+    // Время переноса в корзину; у живых постов None
+    pub deleted_at: Option<DateTime<Utc>>,
+    // Растёт при каждом изменении, отдаётся клиентам как ETag
+    pub version: i32,
+}
+
+// Пост в корзине вместе со временем, когда он будет удалён окончательно
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedPost {
+    #[serde(flatten)]
+    pub post: Post,
+    pub purge_at: DateTime<Utc>,
+}
+
+impl Post {
+    // Черновики и архив видит только автор
+    pub fn is_visible_to(&self, viewer_id: Option<i64>) -> bool {
+        self.status == PostStatus::Published || viewer_id == Some(self.author_id)
+    }
+}
+
+// Результат поиска по slug: либо актуальный адрес, либо устаревший, с которого нужно перенаправить
+pub enum SlugLookup {
+    Current(Post),
+    Moved(Post),
 }
 
 pub struct NewPost {
     pub title: String,
     pub content: String,
+    // Заполняет сервис через with_content_html
+    pub content_html: String,
     pub author_id: i64,
+    // None — оставить теги как есть (при создании — без тегов)
+    pub tags: Option<Vec<String>>,
+    pub status: PostStatus,
     pub created_at: DateTime<Utc>,
 }
 
@@ -21,9 +146,101 @@ impl NewPost {
     pub fn new(title: String, content: String, author_id: i64) -> Self {
         Self {
             title,
+            content_html: String::new(),
             content,
             author_id,
+            tags: None,
+            status: PostStatus::Published,
             created_at: Utc::now(),
         }
     }
+
+    pub fn with_status(mut self, status: PostStatus) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_tags(mut self, tags: Option<Vec<String>>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    pub fn with_content_html(mut self, content_html: String) -> Self {
+        self.content_html = content_html;
+        self
+    }
+}
+
+// Частичное изменение поста: None — поле остаётся как есть
+#[derive(Debug, Clone, Default)]
+pub struct PostPatch {
+    pub title: Option<String>,
+    pub content: Option<String>,
+    // Пустой список убирает все теги
+    pub tags: Option<Vec<String>>,
+    // HTML для content, рендерит сервис; от клиентов не принимается
+    pub content_html: Option<String>,
+}
+
+impl PostPatch {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.content.is_none() && self.tags.is_none()
+    }
+
+    // Проверяются только переданные поля
+    pub fn validate(&self) -> Result<(), DomainError> {
+        if let Some(title) = &self.title {
+            validate_title(title)?;
+        }
+        if let Some(content) = &self.content {
+            validate_content(content)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn validate_title(title: &str) -> Result<(), DomainError> {
+    if title.trim().is_empty() {
+        return Err(DomainError::Validation("title cannot be empty".into()));
+    }
+    if title.chars().count() > MAX_TITLE_LEN {
+        return Err(DomainError::Validation(format!(
+            "title is too long (max {} characters)",
+            MAX_TITLE_LEN
+        )));
+    }
+    Ok(())
+}
+
+pub fn validate_content(content: &str) -> Result<(), DomainError> {
+    if content.trim().is_empty() {
+        return Err(DomainError::Validation("content cannot be empty".into()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patch_validates_only_provided_fields() {
+        let patch = PostPatch {
+            content: Some("new text".into()),
+            ..Default::default()
+        };
+        assert!(patch.validate().is_ok());
+
+        let patch = PostPatch {
+            title: Some("   ".into()),
+            ..Default::default()
+        };
+        assert!(patch.validate().is_err());
+
+        let patch = PostPatch {
+            title: Some("x".repeat(MAX_TITLE_LEN + 1)),
+            ..Default::default()
+        };
+        assert!(patch.validate().is_err());
+    }
 }
@@ -0,0 +1,357 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+use crate::domain::post::{Post, PostStatus};
+use crate::domain::tag::normalize_tag;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostSortField {
+    CreatedAt,
+    UpdatedAt,
+    // Есть только у опубликованных постов
+    PublishedAt,
+    Title,
+    AuthorId,
+    Id,
+}
+
+impl PostSortField {
+    pub fn parse(name: &str) -> Result<Self, DomainError> {
+        match name {
+            "created_at" => Ok(Self::CreatedAt),
+            "updated_at" => Ok(Self::UpdatedAt),
+            "published_at" => Ok(Self::PublishedAt),
+            "title" => Ok(Self::Title),
+            "author_id" => Ok(Self::AuthorId),
+            "id" => Ok(Self::Id),
+            other => Err(DomainError::Validation(format!(
+                "unknown sort field: {}",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::CreatedAt => "created_at",
+            Self::UpdatedAt => "updated_at",
+            Self::PublishedAt => "published_at",
+            Self::Title => "title",
+            Self::AuthorId => "author_id",
+            Self::Id => "id",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PostSort {
+    pub field: PostSortField,
+    pub descending: bool,
+}
+
+impl PostSort {
+    // Разбирает строку вида "-created_at,title"; id всегда добавляется последним ключом,
+    // чтобы порядок был однозначным и годился для keyset-пагинации
+    pub fn parse_list(spec: Option<&str>) -> Result<Vec<Self>, DomainError> {
+        let mut sort: Vec<Self> = Vec::new();
+        for part in spec.unwrap_or("-created_at").split(',') {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            let (descending, name) = match part.strip_prefix('-') {
+                Some(name) => (true, name),
+                None => (false, part.strip_prefix('+').unwrap_or(part)),
+            };
+            let field = PostSortField::parse(name)?;
+            if sort.iter().any(|s| s.field == field) {
+                return Err(DomainError::Validation(format!(
+                    "duplicate sort field: {}",
+                    name
+                )));
+            }
+            sort.push(Self { field, descending });
+        }
+        if sort.is_empty() {
+            return Err(DomainError::Validation("empty sort".into()));
+        }
+        if !sort.iter().any(|s| s.field == PostSortField::Id) {
+            let descending = sort[0].descending;
+            sort.push(Self {
+                field: PostSortField::Id,
+                descending,
+            });
+        }
+        Ok(sort)
+    }
+
+    pub fn to_spec(sort: &[Self]) -> String {
+        sort.iter()
+            .map(|s| format!("{}{}", if s.descending { "-" } else { "" }, s.field.name()))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PostFilter {
+    // Кто смотрит ленту: помимо опубликованных ему видны собственные черновики и архив
+    pub viewer_id: Option<i64>,
+    pub author_id: Option<i64>,
+    pub status: Option<PostStatus>,
+    pub tag: Option<String>,
+    // Лента подписок: только посты авторов, на которых подписан этот пользователь
+    pub followed_by: Option<i64>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+// Сырые параметры списка постов в том виде, в каком они пришли из HTTP или gRPC
+#[derive(Debug, Clone, Default)]
+pub struct PostListParams {
+    pub viewer_id: Option<i64>,
+    pub author_id: Option<i64>,
+    pub status: Option<String>,
+    pub tag: Option<String>,
+    pub followed_by: Option<i64>,
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    pub sort: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PostQuery {
+    pub filter: PostFilter,
+    pub sort: Vec<PostSort>,
+    pub after: Option<PostCursor>,
+    pub limit: i64,
+}
+
+impl PostQuery {
+    pub fn from_params(params: PostListParams, limit: i64) -> Result<Self, DomainError> {
+        let filter = PostFilter {
+            viewer_id: params.viewer_id,
+            author_id: params.author_id,
+            status: params
+                .status
+                .as_deref()
+                .map(PostStatus::parse)
+                .transpose()?,
+            tag: params.tag.as_deref().map(normalize_tag).transpose()?,
+            followed_by: params.followed_by,
+            created_after: params
+                .created_after
+                .as_deref()
+                .map(parse_timestamp)
+                .transpose()?,
+            created_before: params
+                .created_before
+                .as_deref()
+                .map(parse_timestamp)
+                .transpose()?,
+        };
+        if let (Some(after), Some(before)) = (filter.created_after, filter.created_before)
+            && after >= before
+        {
+            return Err(DomainError::Validation(
+                "created_after must be earlier than created_before".into(),
+            ));
+        }
+        let sort = PostSort::parse_list(params.sort.as_deref())?;
+        if sort.iter().any(|s| s.field == PostSortField::PublishedAt)
+            && filter.status != Some(PostStatus::Published)
+        {
+            return Err(DomainError::Validation(
+                "sorting by published_at requires status=published".into(),
+            ));
+        }
+        let after = params
+            .cursor
+            .as_deref()
+            .filter(|c| !c.is_empty())
+            .map(PostCursor::decode)
+            .transpose()?;
+        if let Some(cursor) = &after
+            && cursor.sort != PostSort::to_spec(&sort)
+        {
+            return Err(DomainError::Validation(
+                "cursor does not match sort order".into(),
+            ));
+        }
+        Ok(Self {
+            filter,
+            sort,
+            after,
+            limit,
+        })
+    }
+}
+
+// Принимает RFC 3339 ("2026-01-11T12:00:00Z") или дату ("2026-01-11", полночь UTC)
+pub fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, DomainError> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+        .ok_or_else(|| DomainError::Validation(format!("invalid date: {}", value)))
+}
+
+// Позиция в ленте для keyset-пагинации: значения ключей сортировки последнего отданного поста
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostCursor {
+    #[serde(rename = "s")]
+    pub sort: String,
+    #[serde(rename = "c")]
+    pub created_at: DateTime<Utc>,
+    #[serde(rename = "u")]
+    pub updated_at: DateTime<Utc>,
+    #[serde(rename = "p", default, skip_serializing_if = "Option::is_none")]
+    pub published_at: Option<DateTime<Utc>>,
+    #[serde(rename = "t")]
+    pub title: String,
+    #[serde(rename = "a")]
+    pub author_id: i64,
+    #[serde(rename = "i")]
+    pub id: i64,
+}
+
+impl PostCursor {
+    pub fn new(post: &Post, sort: &[PostSort]) -> Self {
+        Self {
+            sort: PostSort::to_spec(sort),
+            created_at: post.created_at,
+            updated_at: post.updated_at,
+            published_at: post.published_at,
+            title: post.title.clone(),
+            author_id: post.author_id,
+            id: post.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let raw = serde_json::to_vec(self).expect("cursor is always serializable");
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(token: &str) -> Result<Self, DomainError> {
+        let raw = URL_SAFE_NO_PAD
+            .decode(token)
+            .map_err(|_| DomainError::Validation("invalid cursor".into()))?;
+        serde_json::from_slice(&raw).map_err(|_| DomainError::Validation("invalid cursor".into()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PostPage {
+    pub posts: Vec<Post>,
+    pub next_cursor: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_params(cursor: Option<String>) -> PostListParams {
+        PostListParams {
+            status: Some("published".into()),
+            followed_by: Some(1),
+            sort: Some("-published_at".into()),
+            cursor,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_sort_list() {
+        let sort = PostSort::parse_list(Some("title, +author_id")).unwrap();
+        assert_eq!(PostSort::to_spec(&sort), "title,author_id,id");
+        let sort = PostSort::parse_list(Some("-updated_at,id")).unwrap();
+        assert_eq!(PostSort::to_spec(&sort), "-updated_at,id");
+        let sort = PostSort::parse_list(None).unwrap();
+        assert_eq!(PostSort::to_spec(&sort), "-created_at,-id");
+
+        for spec in ["views", "-", ",", "created_at,-created_at", "id,title,id"] {
+            assert!(PostSort::parse_list(Some(spec)).is_err(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn published_at_sort_requires_published_status() {
+        let query = PostQuery::from_params(feed_params(None), 21).unwrap();
+        assert_eq!(PostSort::to_spec(&query.sort), "-published_at,-id");
+
+        for status in [None, Some("draft".to_string())] {
+            let params = PostListParams {
+                status,
+                ..feed_params(None)
+            };
+            assert!(PostQuery::from_params(params, 21).is_err());
+        }
+    }
+
+    #[test]
+    fn cursor_must_match_sort() {
+        let params = |sort: &str, cursor: &PostCursor| PostListParams {
+            sort: Some(sort.into()),
+            cursor: Some(cursor.encode()),
+            ..Default::default()
+        };
+        let by_title = PostCursor {
+            sort: "title,id".into(),
+            created_at: parse_timestamp("2026-01-01").unwrap(),
+            updated_at: parse_timestamp("2026-01-02").unwrap(),
+            published_at: None,
+            title: "Post".into(),
+            author_id: 2,
+            id: 7,
+        };
+        let query = PostQuery::from_params(params("title", &by_title), 21).unwrap();
+        assert_eq!(query.after, Some(by_title.clone()));
+
+        for sort in ["-title", "title,-id", "-created_at"] {
+            assert!(
+                PostQuery::from_params(params(sort, &by_title), 21).is_err(),
+                "{}",
+                sort
+            );
+        }
+    }
+
+    #[test]
+    fn feed_cursor_keeps_publication_time() {
+        let published_at = parse_timestamp("2026-10-16T12:00:00Z").unwrap();
+        let cursor = PostCursor {
+            sort: "-published_at,-id".into(),
+            created_at: parse_timestamp("2026-01-01").unwrap(),
+            updated_at: published_at,
+            published_at: Some(published_at),
+            title: "Post".into(),
+            author_id: 2,
+            id: 7,
+        };
+        let query = PostQuery::from_params(feed_params(Some(cursor.encode())), 21).unwrap();
+        assert_eq!(query.after, Some(cursor));
+
+        // Курсор обычного списка не подходит к ленте
+        let created = PostCursor {
+            sort: "-created_at,-id".into(),
+            published_at: None,
+            ..query.after.unwrap()
+        };
+        assert!(PostQuery::from_params(feed_params(Some(created.encode())), 21).is_err());
+    }
+
+    #[test]
+    fn cursor_without_updated_at_is_invalid() {
+        let raw = r#"{"s":"-created_at,-id","c":"2026-01-01T00:00:00Z","t":"Post","a":2,"i":7}"#;
+        assert!(PostCursor::decode(&URL_SAFE_NO_PAD.encode(raw)).is_err());
+    }
+}
@@ -0,0 +1,162 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+use crate::domain::post::Post;
+
+pub const MAX_SEARCH_QUERY_LEN: usize = 256;
+
+// Маркеры подсветки, которые возвращает ts_headline; после экранирования заменяются на <mark>
+pub const HIGHLIGHT_START: &str = "\u{E000}";
+pub const HIGHLIGHT_STOP: &str = "\u{E001}";
+
+#[derive(Debug, Clone)]
+pub struct PostSearchQuery {
+    pub text: String,
+    pub viewer_id: Option<i64>,
+    pub after: Option<SearchCursor>,
+    pub limit: i64,
+}
+
+impl PostSearchQuery {
+    pub fn new(
+        text: &str,
+        viewer_id: Option<i64>,
+        cursor: Option<&str>,
+        limit: i64,
+    ) -> Result<Self, DomainError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(DomainError::Validation("search query is empty".into()));
+        }
+        if text.chars().count() > MAX_SEARCH_QUERY_LEN {
+            return Err(DomainError::Validation(format!(
+                "search query is longer than {} characters",
+                MAX_SEARCH_QUERY_LEN
+            )));
+        }
+        let after = cursor
+            .filter(|c| !c.is_empty())
+            .map(SearchCursor::decode)
+            .transpose()?;
+        Ok(Self {
+            text: text.to_string(),
+            viewer_id,
+            after,
+            limit,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PostSearchHit {
+    pub post: Post,
+    pub rank: f32,
+    pub title_highlight: String,
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PostSearchPage {
+    pub hits: Vec<PostSearchHit>,
+    pub next_cursor: Option<String>,
+}
+
+// Позиция в выдаче поиска: (rank, id) последнего отданного результата
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SearchCursor {
+    #[serde(rename = "r")]
+    pub rank: f32,
+    #[serde(rename = "i")]
+    pub id: i64,
+}
+
+impl SearchCursor {
+    pub fn encode(&self) -> String {
+        let raw = serde_json::to_vec(self).expect("cursor is always serializable");
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    pub fn decode(token: &str) -> Result<Self, DomainError> {
+        let raw = URL_SAFE_NO_PAD
+            .decode(token)
+            .map_err(|_| DomainError::Validation("invalid cursor".into()))?;
+        serde_json::from_slice(&raw).map_err(|_| DomainError::Validation("invalid cursor".into()))
+    }
+}
+
+impl From<&PostSearchHit> for SearchCursor {
+    fn from(hit: &PostSearchHit) -> Self {
+        Self {
+            rank: hit.rank,
+            id: hit.post.id,
+        }
+    }
+}
+
+// Экранирует текст фрагмента и превращает маркеры ts_headline в <mark>
+pub fn highlight_to_html(raw: &str) -> String {
+    let mut html = String::with_capacity(raw.len());
+    for ch in raw.chars() {
+        match ch {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            _ => html.push(ch),
+        }
+    }
+    html.replace(HIGHLIGHT_START, "<mark>")
+        .replace(HIGHLIGHT_STOP, "</mark>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_is_trimmed_and_limited() {
+        let query = PostSearchQuery::new("  rust async  ", Some(3), None, 21).unwrap();
+        assert_eq!(query.text, "rust async");
+        assert_eq!(query.viewer_id, Some(3));
+        assert!(query.after.is_none());
+        assert_eq!(query.limit, 21);
+
+        for text in ["", "   ", "\t\n"] {
+            assert!(PostSearchQuery::new(text, None, None, 21).is_err());
+        }
+        // Длина считается в символах, а не в байтах
+        let longest = "я".repeat(MAX_SEARCH_QUERY_LEN);
+        assert!(PostSearchQuery::new(&longest, None, None, 21).is_ok());
+        let too_long = format!("{}я", longest);
+        assert!(PostSearchQuery::new(&too_long, None, None, 21).is_err());
+    }
+
+    #[test]
+    fn cursor_keeps_exact_rank() {
+        // ts_rank даёт дробные ранги; после округления (rank, id) < курсор пропустил бы
+        // или повторил результаты
+        for rank in [0.1_f32, 0.060_792_7, 1e-20, f32::MAX] {
+            let cursor = SearchCursor { rank, id: 42 };
+            let decoded = SearchCursor::decode(&cursor.encode()).unwrap();
+            assert_eq!(decoded.rank.to_bits(), rank.to_bits());
+            assert_eq!(decoded.id, 42);
+
+            let query = PostSearchQuery::new("rust", None, Some(&cursor.encode()), 21).unwrap();
+            assert_eq!(query.after, Some(cursor));
+        }
+
+        // Пустой курсор означает первую страницу
+        let query = PostSearchQuery::new("rust", None, Some(""), 21).unwrap();
+        assert!(query.after.is_none());
+        for token in [
+            "garbage",
+            "e30",
+            &URL_SAFE_NO_PAD.encode(r#"{"r":"high","i":1}"#),
+        ] {
+            assert!(PostSearchQuery::new("rust", None, Some(token), 21).is_err());
+        }
+    }
+}
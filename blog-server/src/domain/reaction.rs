@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReactionKind {
+    Like,
+    Love,
+    Laugh,
+    Wow,
+    Sad,
+    Fire,
+}
+
+impl ReactionKind {
+    // Порядок, в котором реакции отдаются клиентам
+    pub const ALL: [ReactionKind; 6] = [
+        Self::Like,
+        Self::Love,
+        Self::Laugh,
+        Self::Wow,
+        Self::Sad,
+        Self::Fire,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Like => "like",
+            Self::Love => "love",
+            Self::Laugh => "laugh",
+            Self::Wow => "wow",
+            Self::Sad => "sad",
+            Self::Fire => "fire",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Self::Like => "👍",
+            Self::Love => "❤️",
+            Self::Laugh => "😂",
+            Self::Wow => "😮",
+            Self::Sad => "😢",
+            Self::Fire => "🔥",
+        }
+    }
+
+    pub fn parse(value: &str) -> Result<Self, DomainError> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == value)
+            .ok_or_else(|| DomainError::Validation(format!("unknown reaction: {}", value)))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactionCount {
+    pub kind: ReactionKind,
+    pub emoji: String,
+    pub count: i64,
+    // Отреагировал ли текущий пользователь; без пользователя всегда false
+    pub reacted: bool,
+}
+
+// Сводка по всем видам реакций, включая нулевые, чтобы клиенту не нужно было знать набор
+pub fn summarize_reactions(counts: &HashMap<String, i64>) -> Vec<ReactionCount> {
+    ReactionKind::ALL
+        .into_iter()
+        .map(|kind| ReactionCount {
+            kind,
+            emoji: kind.emoji().to_string(),
+            count: counts.get(kind.as_str()).copied().unwrap_or_default(),
+            reacted: false,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_covers_every_kind_in_order() {
+        let counts = HashMap::from([("fire".to_string(), 3), ("unknown".to_string(), 7)]);
+        let summary = summarize_reactions(&counts);
+        let kinds: Vec<ReactionKind> = summary.iter().map(|r| r.kind).collect();
+        assert_eq!(kinds, ReactionKind::ALL);
+        assert_eq!(summary.last().unwrap().count, 3);
+        assert!(summary.iter().all(|r| !r.reacted));
+        assert!(ReactionKind::parse("Like").is_err());
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PostRevision {
+    pub post_id: i64,
+    pub revision: i32,
+    pub title: String,
+    pub content: String,
+    // Кто сохранил эту версию
+    pub editor_id: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+impl DiffOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equal => "equal",
+            Self::Insert => "insert",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RevisionDiff {
+    pub post_id: i64,
+    pub from_revision: i32,
+    pub to_revision: i32,
+    pub title: Vec<DiffLine>,
+    pub content: Vec<DiffLine>,
+}
+
+impl RevisionDiff {
+    pub fn between(from: &PostRevision, to: &PostRevision) -> Self {
+        Self {
+            post_id: to.post_id,
+            from_revision: from.revision,
+            to_revision: to.revision,
+            title: line_diff(&from.title, &to.title),
+            content: line_diff(&from.content, &to.content),
+        }
+    }
+}
+
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    // Иначе последняя строка без перевода строки считается изменённой
+    let old = with_trailing_newline(old);
+    let new = with_trailing_newline(new);
+    TextDiff::from_lines(&old, &new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            op: match change.tag() {
+                ChangeTag::Equal => DiffOp::Equal,
+                ChangeTag::Insert => DiffOp::Insert,
+                ChangeTag::Delete => DiffOp::Delete,
+            },
+            text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+        })
+        .collect()
+}
+
+fn with_trailing_newline(text: &str) -> String {
+    if text.is_empty() || text.ends_with('\n') {
+        text.to_string()
+    } else {
+        format!("{}\n", text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_diff_marks_changed_lines() {
+        let diff = line_diff("a\nb\nc", "a\nB\nc\nd");
+        let ops: Vec<(DiffOp, &str)> = diff.iter().map(|l| (l.op, l.text.as_str())).collect();
+        assert_eq!(
+            ops,
+            vec![
+                (DiffOp::Equal, "a"),
+                (DiffOp::Delete, "b"),
+                (DiffOp::Insert, "B"),
+                (DiffOp::Equal, "c"),
+                (DiffOp::Insert, "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn line_diff_of_identical_text_is_all_equal() {
+        let diff = line_diff("same\ntext\n", "same\ntext");
+        assert!(diff.iter().all(|l| l.op == DiffOp::Equal));
+        assert_eq!(diff.len(), 2);
+    }
+}
@@ -0,0 +1,199 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::error::DomainError;
+use crate::domain::post::{PostStatus, validate_title};
+
+pub const MAX_DESCRIPTION_LEN: usize = 2000;
+pub const MAX_SERIES_POSTS: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Series {
+    pub id: i64,
+    pub owner_id: i64,
+    pub title: String,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub struct NewSeries {
+    pub owner_id: i64,
+    pub title: String,
+    pub description: String,
+}
+
+impl NewSeries {
+    pub fn validate(&self) -> Result<(), DomainError> {
+        validate_title(&self.title)?;
+        if self.description.chars().count() > MAX_DESCRIPTION_LEN {
+            return Err(DomainError::Validation(format!(
+                "description is too long (max {} characters)",
+                MAX_DESCRIPTION_LEN
+            )));
+        }
+        Ok(())
+    }
+}
+
+// Строка оглавления серии
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesEntry {
+    pub position: i32,
+    pub id: i64,
+    pub title: String,
+    pub slug: String,
+    pub status: PostStatus,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesContents {
+    #[serde(flatten)]
+    pub series: Series,
+    pub posts: Vec<SeriesEntry>,
+}
+
+// Ссылка на соседнюю часть серии
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostLink {
+    pub id: i64,
+    pub slug: String,
+    pub title: String,
+}
+
+// Место поста в серии; previous/next — ближайшие опубликованные части
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesNavigation {
+    pub id: i64,
+    pub title: String,
+    pub position: i32,
+    pub previous: Option<PostLink>,
+    pub next: Option<PostLink>,
+}
+
+// Место поста в серии, как его отдаёт база: все части с признаком публикации
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeriesPlacement {
+    pub id: i64,
+    pub title: String,
+    pub position: i32,
+    pub parts: Vec<SeriesPart>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SeriesPart {
+    pub position: i32,
+    // Опубликован и не удалён
+    pub published: bool,
+    #[serde(flatten)]
+    pub link: PostLink,
+}
+
+// Соседями становятся ближайшие опубликованные части, черновики между ними пропускаются
+pub fn series_navigation(placement: SeriesPlacement) -> SeriesNavigation {
+    let mut previous: Option<SeriesPart> = None;
+    let mut next: Option<SeriesPart> = None;
+    for part in placement.parts.into_iter().filter(|part| part.published) {
+        if part.position < placement.position {
+            if previous.as_ref().is_none_or(|p| p.position < part.position) {
+                previous = Some(part);
+            }
+        } else if part.position > placement.position
+            && next.as_ref().is_none_or(|n| n.position > part.position)
+        {
+            next = Some(part);
+        }
+    }
+    SeriesNavigation {
+        id: placement.id,
+        title: placement.title,
+        position: placement.position,
+        previous: previous.map(|part| part.link),
+        next: next.map(|part| part.link),
+    }
+}
+
+// Порядок постов задаётся порядком id в списке
+pub fn validate_series_posts(post_ids: &[i64]) -> Result<(), DomainError> {
+    if post_ids.len() > MAX_SERIES_POSTS {
+        return Err(DomainError::Validation(format!(
+            "too many posts in series (max {})",
+            MAX_SERIES_POSTS
+        )));
+    }
+    if let Some((i, id)) = post_ids
+        .iter()
+        .enumerate()
+        .find(|(i, id)| post_ids[..*i].contains(id))
+    {
+        return Err(DomainError::Validation(format!(
+            "post {} is listed twice (position {})",
+            id,
+            i + 1
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_duplicate_and_excess_posts() {
+        assert!(validate_series_posts(&[]).is_ok());
+        assert!(validate_series_posts(&[3, 1, 2]).is_ok());
+        assert!(validate_series_posts(&[3, 1, 3]).is_err());
+        let many: Vec<i64> = (1..=MAX_SERIES_POSTS as i64 + 1).collect();
+        assert!(validate_series_posts(&many).is_err());
+    }
+
+    fn part(position: i32, published: bool) -> SeriesPart {
+        SeriesPart {
+            position,
+            published,
+            link: PostLink {
+                id: position as i64 * 10,
+                slug: format!("part-{}", position),
+                title: format!("Part {}", position),
+            },
+        }
+    }
+
+    #[test]
+    fn navigation_skips_unpublished_parts() {
+        let placement = |position, parts| SeriesPlacement {
+            id: 1,
+            title: "Series".into(),
+            position,
+            parts,
+        };
+        let parts = || {
+            vec![
+                part(5, true),
+                part(1, true),
+                part(2, false),
+                part(3, true),
+                part(4, false),
+            ]
+        };
+
+        let nav = series_navigation(placement(3, parts()));
+        assert_eq!(nav.position, 3);
+        assert_eq!(nav.previous.unwrap().slug, "part-1");
+        assert_eq!(nav.next.unwrap().slug, "part-5");
+
+        // Черновик тоже получает ссылки на опубликованных соседей
+        let nav = series_navigation(placement(2, parts()));
+        assert_eq!(nav.previous.unwrap().id, 10);
+        assert_eq!(nav.next.unwrap().id, 30);
+
+        let nav = series_navigation(placement(1, parts()));
+        assert!(nav.previous.is_none());
+        assert_eq!(nav.next.unwrap().id, 30);
+
+        let nav = series_navigation(placement(5, vec![part(4, false), part(5, true)]));
+        assert!(nav.previous.is_none() && nav.next.is_none());
+    }
+}
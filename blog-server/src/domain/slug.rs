@@ -0,0 +1,106 @@
+pub const MAX_SLUG_LEN: usize = 80;
+
+// URL-безопасный slug из заголовка: транслитерация кириллицы, латиница и цифры,
+// остальное схлопывается в одиночные дефисы
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+    for ch in title.chars().flat_map(char::to_lowercase) {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch);
+        } else if let Some(latin) = transliterate(ch) {
+            slug.push_str(latin);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let mut slug = slug.trim_end_matches('-').to_string();
+    if slug.len() > MAX_SLUG_LEN {
+        slug.truncate(MAX_SLUG_LEN);
+        slug = slug.trim_end_matches('-').to_string();
+    }
+    if slug.is_empty() {
+        slug.push_str("post");
+    }
+    slug
+}
+
+// Проверяет, что slug получен из base (сам base или base-N)
+pub fn matches_base(slug: &str, base: &str) -> bool {
+    match slug.strip_prefix(base) {
+        Some("") => true,
+        Some(rest) => rest
+            .strip_prefix('-')
+            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())),
+        None => false,
+    }
+}
+
+fn transliterate(ch: char) -> Option<&'static str> {
+    let latin = match ch {
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' => "g",
+        'д' => "d",
+        'е' => "e",
+        'ё' => "yo",
+        'ж' => "zh",
+        'з' => "z",
+        'и' => "i",
+        'й' => "y",
+        'к' => "k",
+        'л' => "l",
+        'м' => "m",
+        'н' => "n",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ы' => "y",
+        'э' => "e",
+        'ю' => "yu",
+        'я' => "ya",
+        'і' => "i",
+        'ї' => "yi",
+        'є' => "ye",
+        'ґ' => "g",
+        _ => return None,
+    };
+    Some(latin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slugify_transliterates_cyrillic() {
+        assert_eq!(slugify("Привет, мир!"), "privet-mir");
+        assert_eq!(slugify("Щука и ёжик"), "shchuka-i-yozhik");
+        assert_eq!(slugify("Объявление"), "obyavlenie");
+    }
+
+    #[test]
+    fn slugify_collapses_separators_and_falls_back() {
+        assert_eq!(slugify("  Rust -- 2024 Edition  "), "rust-2024-edition");
+        assert_eq!(slugify("!!!"), "post");
+        assert!(slugify(&"a".repeat(200)).len() <= MAX_SLUG_LEN);
+    }
+
+    #[test]
+    fn matches_base_accepts_numeric_suffix_only() {
+        assert!(matches_base("hello", "hello"));
+        assert!(matches_base("hello-3", "hello"));
+        assert!(!matches_base("hello-world", "hello"));
+        assert!(!matches_base("hello-", "hello"));
+    }
+}
@@ -0,0 +1,84 @@
+use serde::Serialize;
+
+use crate::domain::error::DomainError;
+
+pub const MAX_TAG_LEN: usize = 32;
+pub const MAX_TAGS_PER_POST: usize = 10;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagCount {
+    pub name: String,
+    // Только опубликованные посты
+    pub post_count: i64,
+}
+
+// Приводит тег к каноническому виду: нижний регистр, пробелы заменяются дефисом.
+// Разрешены буквы, цифры и "-_.+#" (чтобы проходили "c++" и "c#")
+pub fn normalize_tag(name: &str) -> Result<String, DomainError> {
+    let tag = name
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase();
+    if tag.is_empty() {
+        return Err(DomainError::Validation("tag cannot be empty".into()));
+    }
+    if tag.chars().count() > MAX_TAG_LEN {
+        return Err(DomainError::Validation(format!(
+            "tag is too long (max {} characters): {}",
+            MAX_TAG_LEN, tag
+        )));
+    }
+    if let Some(ch) = tag
+        .chars()
+        .find(|c| !c.is_alphanumeric() && !"-_.+#".contains(*c))
+    {
+        return Err(DomainError::Validation(format!(
+            "invalid character '{}' in tag: {}",
+            ch, tag
+        )));
+    }
+    Ok(tag)
+}
+
+// Нормализует, убирает дубликаты и сортирует список тегов поста
+pub fn normalize_tags(names: &[String]) -> Result<Vec<String>, DomainError> {
+    let mut tags = names
+        .iter()
+        .map(|name| normalize_tag(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    tags.sort();
+    tags.dedup();
+    if tags.len() > MAX_TAGS_PER_POST {
+        return Err(DomainError::Validation(format!(
+            "too many tags (max {})",
+            MAX_TAGS_PER_POST
+        )));
+    }
+    Ok(tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_tags_lowercases_and_dedups() {
+        let tags = normalize_tags(&[
+            "Rust".to_string(),
+            " rust ".to_string(),
+            "Web  Dev".to_string(),
+            "C++".to_string(),
+            "Базы данных".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(tags, vec!["c++", "rust", "web-dev", "базы-данных"]);
+    }
+
+    #[test]
+    fn normalize_tag_rejects_invalid_names() {
+        assert!(normalize_tag("   ").is_err());
+        assert!(normalize_tag("<script>").is_err());
+        assert!(normalize_tag(&"a".repeat(MAX_TAG_LEN + 1)).is_err());
+    }
+}
@@ -1,4 +1,10 @@
+use std::net::IpAddr;
+
+use hmac::{Hmac, Mac};
 use serde::Deserialize;
+use sha2::Sha256;
+
+use crate::domain::media::{DerivativeSize, parse_derivative_sizes};
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct AppConfig {
@@ -8,8 +14,55 @@ pub struct AppConfig {
     pub jwt_secret: String,
     #[serde(default)]
     pub cors_origins: Vec<String>,
+    #[allow(dead_code)]
     pub exchange_api_url: String,
     pub grpc_port: u16,
+    pub search_language: String,
+    pub publish_interval_secs: u64,
+    // Внешний адрес сайта для абсолютных ссылок в RSS/Atom/JSON Feed
+    pub site_url: String,
+    pub site_title: String,
+    // Ссылка на страницу поста на сайте для лент, с подстановками {slug} и {id}
+    pub post_url: String,
+    // В ленты попадает анонс вместо полного текста поста
+    pub feed_excerpts: bool,
+    // Сколько дней удалённый пост можно восстановить из корзины
+    pub trash_retention_days: i64,
+    pub purge_interval_secs: u64,
+    // PUT/DELETE поста без If-Match отклоняются с 428
+    pub require_if_match: bool,
+    // Cache-Control для GET-ответов без токена и с токеном
+    pub cache_control_public: String,
+    pub cache_control_private: String,
+    pub media_storage: MediaStorageConfig,
+    // Адрес, по которому отдаются загруженные файлы; по умолчанию {site_url}/media
+    pub media_url: String,
+    // Размер одного файла и суммарный объём загрузок одного пользователя, в байтах
+    pub media_max_file_bytes: i64,
+    pub media_quota_bytes: i64,
+    // Уменьшенные копии картинок и число потоков, которые их строят
+    pub media_derivatives: Vec<DerivativeSize>,
+    pub media_workers: usize,
+    // Как часто накопленные в памяти просмотры постов записываются в базу
+    pub view_flush_interval_secs: u64,
+    // Ключ хэшей посетителей; по умолчанию выводится из JWT_SECRET
+    pub view_hash_secret: String,
+    // Прокси, которым можно доверить X-Forwarded-For с адресом клиента
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub enum MediaStorageConfig {
+    Local {
+        dir: String,
+    },
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key: String,
+        secret_key: String,
+    },
 }
 
 impl AppConfig {
@@ -37,6 +90,84 @@ impl AppConfig {
             .unwrap_or_else(|_| "50051".into())
             .parse()
             .map_err(|e| anyhow::anyhow!("invalid PORT: {}", e))?;
+        let search_language = std::env::var("SEARCH_LANGUAGE").unwrap_or_else(|_| "russian".into());
+        if search_language.is_empty()
+            || !search_language.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+        {
+            return Err(anyhow::anyhow!("invalid SEARCH_LANGUAGE: {}", search_language));
+        }
+        let publish_interval_secs = positive_from_env("PUBLISH_INTERVAL_SECS", 30)? as u64;
+        let site_url = std::env::var("SITE_URL")
+            .unwrap_or_else(|_| format!("http://{}:{}", host, port))
+            .trim_end_matches('/')
+            .to_string();
+        let site_title = std::env::var("SITE_TITLE").unwrap_or_else(|_| "Blog".into());
+        let post_url =
+            std::env::var("POST_URL").unwrap_or_else(|_| format!("{}/posts/{{slug}}", site_url));
+        if !post_url.contains("{slug}") && !post_url.contains("{id}") {
+            return Err(anyhow::anyhow!("invalid POST_URL: {}", post_url));
+        }
+        let feed_excerpts = match std::env::var("FEED_CONTENT").as_deref() {
+            Err(_) | Ok("full") => false,
+            Ok("excerpt") => true,
+            Ok(other) => return Err(anyhow::anyhow!("invalid FEED_CONTENT: {}", other)),
+        };
+        let trash_retention_days = std::env::var("TRASH_RETENTION_DAYS")
+            .unwrap_or_else(|_| "30".into())
+            .parse()
+            .ok()
+            .filter(|days| *days > 0)
+            .ok_or_else(|| anyhow::anyhow!("invalid TRASH_RETENTION_DAYS"))?;
+        let purge_interval_secs = positive_from_env("PURGE_INTERVAL_SECS", 3600)? as u64;
+        let require_if_match = match std::env::var("REQUIRE_IF_MATCH").as_deref() {
+            Err(_) | Ok("false") => false,
+            Ok("true") => true,
+            Ok(other) => return Err(anyhow::anyhow!("invalid REQUIRE_IF_MATCH: {}", other)),
+        };
+        let cache_control_public =
+            cache_control_from_env("CACHE_CONTROL_PUBLIC", "public, max-age=60")?;
+        let cache_control_private =
+            cache_control_from_env("CACHE_CONTROL_PRIVATE", "private, no-cache")?;
+        let media_storage = match std::env::var("MEDIA_STORAGE").as_deref() {
+            Err(_) | Ok("local") => MediaStorageConfig::Local {
+                dir: std::env::var("MEDIA_DIR").unwrap_or_else(|_| "./media".into()),
+            },
+            Ok("s3") => MediaStorageConfig::S3 {
+                endpoint: required_env("S3_ENDPOINT")?,
+                bucket: required_env("S3_BUCKET")?,
+                region: std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".into()),
+                access_key: required_env("S3_ACCESS_KEY")?,
+                secret_key: required_env("S3_SECRET_KEY")?,
+            },
+            Ok(other) => return Err(anyhow::anyhow!("invalid MEDIA_STORAGE: {}", other)),
+        };
+        let media_url = std::env::var("MEDIA_URL")
+            .unwrap_or_else(|_| format!("{}/media", site_url))
+            .trim_end_matches('/')
+            .to_string();
+        let media_max_file_bytes = positive_from_env("MEDIA_MAX_FILE_BYTES", 10 * 1024 * 1024)?;
+        let media_quota_bytes = positive_from_env("MEDIA_QUOTA_BYTES", 100 * 1024 * 1024)?;
+        let media_derivatives = parse_derivative_sizes(
+            &std::env::var("MEDIA_DERIVATIVES")
+                .unwrap_or_else(|_| "thumbnail=320,medium=800,large=1600".into()),
+        )
+        .map_err(|e| anyhow::anyhow!("invalid MEDIA_DERIVATIVES: {}", e))?;
+        let media_workers = positive_from_env("MEDIA_WORKERS", 2)? as usize;
+        let view_flush_interval_secs = positive_from_env("VIEW_FLUSH_INTERVAL_SECS", 10)? as u64;
+        let view_hash_secret = match std::env::var("VIEW_HASH_SECRET") {
+            Ok(secret) if !secret.is_empty() => secret,
+            _ => derive_secret(&jwt_secret, "post-view-visitors"),
+        };
+        let trusted_proxies = std::env::var("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse()
+                    .map_err(|_| anyhow::anyhow!("invalid TRUSTED_PROXIES: {}", s))
+            })
+            .collect::<anyhow::Result<Vec<IpAddr>>>()?;
 
         Ok(Self {
             host,
@@ -46,7 +177,58 @@ impl AppConfig {
             cors_origins,
             exchange_api_url,
             grpc_port,
+            search_language,
+            publish_interval_secs,
+            site_url,
+            site_title,
+            post_url,
+            feed_excerpts,
+            trash_retention_days,
+            purge_interval_secs,
+            require_if_match,
+            cache_control_public,
+            cache_control_private,
+            media_storage,
+            media_url,
+            media_max_file_bytes,
+            media_quota_bytes,
+            media_derivatives,
+            media_workers,
+            view_flush_interval_secs,
+            view_hash_secret,
+            trusted_proxies,
         })
     }
 }
 
+fn required_env(name: &str) -> anyhow::Result<String> {
+    std::env::var(name).map_err(|_| anyhow::anyhow!("{} must be set", name))
+}
+
+fn positive_from_env(name: &str, default: i64) -> anyhow::Result<i64> {
+    match std::env::var(name) {
+        Err(_) => Ok(default),
+        Ok(value) => value
+            .parse()
+            .ok()
+            .filter(|value| *value > 0)
+            .ok_or_else(|| anyhow::anyhow!("invalid {}: {}", name, value)),
+    }
+}
+
+// Отдельный ключ для другой задачи, чтобы один секрет не использовался в двух местах
+fn derive_secret(secret: &str, purpose: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(purpose.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn cache_control_from_env(name: &str, default: &str) -> anyhow::Result<String> {
+    let value = std::env::var(name).unwrap_or_else(|_| default.into());
+    if value.trim().is_empty() || !value.chars().all(|c| c.is_ascii_graphic() || c == ' ') {
+        return Err(anyhow::anyhow!("invalid {}: {}", name, value));
+    }
+    Ok(value)
+}
+
@@ -0,0 +1,393 @@
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader, Limits};
+use thiserror::Error;
+
+use crate::domain::media::DerivativeSize;
+
+const JPEG_QUALITY: u8 = 85;
+const WEBP_QUALITY: f32 = 80.0;
+// Небольшой файл может объявлять огромные размеры и занять при декодировании всю память
+const MAX_DIMENSION: u32 = 12_000;
+const EXIF_ORIENTATION_TAG: u16 = 0x0112;
+
+#[derive(Debug, Error)]
+pub enum ImagingError {
+    #[error("malformed image: {0}")]
+    Malformed(&'static str),
+    #[error("image processing failed: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("webp encoding failed: {0}")]
+    WebP(String),
+}
+
+// Одна уменьшенная копия в одном формате
+pub struct Rendition {
+    pub name: String,
+    pub mime_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+pub struct RenderedImage {
+    // Размеры оригинала с учётом ориентации
+    pub width: u32,
+    pub height: u32,
+    pub renditions: Vec<Rendition>,
+}
+
+// Строит копии каждого размера в WebP и в формате оригинала (JPEG, PNG).
+// Копии не бывают шире оригинала и не содержат метаданных. Ресурсоёмко — только из пула обработки.
+pub fn render_derivatives(
+    data: &[u8],
+    mime_type: &str,
+    sizes: &[DerivativeSize],
+) -> Result<RenderedImage, ImagingError> {
+    let mut reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(image::ImageError::from)?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    reader.limits(limits);
+
+    // Копии потеряли бы анимацию, поэтому у GIF определяются только размеры
+    if mime_type == "image/gif" {
+        let (width, height) = reader.into_dimensions()?;
+        return Ok(RenderedImage {
+            width,
+            height,
+            renditions: Vec::new(),
+        });
+    }
+
+    let mut decoder = reader.into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    let (width, height) = (image.width(), image.height());
+    let fallback = match mime_type {
+        "image/jpeg" => Some("image/jpeg"),
+        "image/png" => Some("image/png"),
+        _ => None,
+    };
+
+    let mut renditions = Vec::new();
+    let mut full_size_rendered = false;
+    for size in sizes {
+        let target_width = size.width.min(width);
+        let resized;
+        let source = if target_width == width {
+            // Размеры шире оригинала дали бы одинаковые копии — достаточно первой
+            if full_size_rendered {
+                continue;
+            }
+            full_size_rendered = true;
+            &image
+        } else {
+            let target_height = (u64::from(height) * u64::from(target_width)
+                + u64::from(width) / 2)
+                / u64::from(width);
+            resized = image.resize_exact(
+                target_width,
+                target_height.max(1) as u32,
+                FilterType::Lanczos3,
+            );
+            &resized
+        };
+        for mime_type in std::iter::once("image/webp").chain(fallback) {
+            renditions.push(Rendition {
+                name: size.name.clone(),
+                mime_type,
+                width: source.width(),
+                height: source.height(),
+                data: encode(source, mime_type)?,
+            });
+        }
+    }
+    Ok(RenderedImage {
+        width,
+        height,
+        renditions,
+    })
+}
+
+fn encode(image: &DynamicImage, mime_type: &str) -> Result<Vec<u8>, ImagingError> {
+    let mut out = Vec::new();
+    match mime_type {
+        "image/webp" => {
+            let (pixels, has_alpha) = if image.color().has_alpha() {
+                (image.to_rgba8().into_raw(), true)
+            } else {
+                (image.to_rgb8().into_raw(), false)
+            };
+            let encoder = if has_alpha {
+                webp::Encoder::from_rgba(&pixels, image.width(), image.height())
+            } else {
+                webp::Encoder::from_rgb(&pixels, image.width(), image.height())
+            };
+            let memory = encoder
+                .encode_simple(false, WEBP_QUALITY)
+                .map_err(|e| ImagingError::WebP(format!("{:?}", e)))?;
+            out.extend_from_slice(&memory);
+        }
+        "image/jpeg" => image
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY))?,
+        _ => image.write_with_encoder(PngEncoder::new(&mut out))?,
+    }
+    Ok(out)
+}
+
+// Убирает EXIF (в том числе координаты GPS), XMP и текстовые комментарии, не перекодируя пиксели.
+// Ориентацию JPEG переносит в минимальный EXIF, иначе снимки с телефона окажутся повёрнутыми.
+pub fn strip_metadata(data: &[u8], mime_type: &str) -> Result<Vec<u8>, ImagingError> {
+    match mime_type {
+        "image/jpeg" => strip_jpeg(data),
+        "image/png" => strip_png(data),
+        "image/webp" => strip_webp(data),
+        _ => Ok(data.to_vec()),
+    }
+}
+
+fn strip_jpeg(data: &[u8]) -> Result<Vec<u8>, ImagingError> {
+    let malformed = || ImagingError::Malformed("truncated JPEG segment");
+    let mut out = Vec::with_capacity(data.len());
+    out.extend_from_slice(data.get(..2).ok_or_else(malformed)?);
+    let mut pos = 2;
+    loop {
+        if pos + 4 > data.len() || data[pos] != 0xFF {
+            return Err(malformed());
+        }
+        let marker = data[pos + 1];
+        match marker {
+            // Байт-заполнитель перед маркером
+            0xFF => {
+                pos += 1;
+                continue;
+            }
+            // Начало сжатых данных: всё остальное копируется как есть
+            0xDA => {
+                out.extend_from_slice(&data[pos..]);
+                return Ok(out);
+            }
+            // Маркеры без длины до SOS не встречаются
+            0x01 | 0xD0..=0xD9 => return Err(malformed()),
+            _ => {}
+        }
+        let len = usize::from(u16::from_be_bytes([data[pos + 2], data[pos + 3]]));
+        let end = pos + 2 + len;
+        if len < 2 || end > data.len() {
+            return Err(malformed());
+        }
+        let payload = &data[pos + 4..end];
+        match marker {
+            // APP1 (EXIF, XMP), APP13 (IPTC), COM
+            0xE1 | 0xED | 0xFE => {
+                if marker == 0xE1
+                    && let Some(tiff) = payload.strip_prefix(b"Exif\0\0")
+                    && let Some(orientation) = exif_orientation(tiff)
+                    && orientation != 1
+                {
+                    out.extend_from_slice(&orientation_segment(orientation));
+                }
+            }
+            _ => out.extend_from_slice(&data[pos..end]),
+        }
+        pos = end;
+    }
+}
+
+// Значение тега Orientation из IFD0; None, если тега нет или EXIF повреждён
+fn exif_orientation(tiff: &[u8]) -> Option<u16> {
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    let u16_at = |offset: usize| {
+        let bytes = [*tiff.get(offset)?, *tiff.get(offset + 1)?];
+        Some(if big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    };
+    let u32_at = |offset: usize| {
+        let bytes: [u8; 4] = tiff.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+    let ifd = u32_at(4)? as usize;
+    let count = usize::from(u16_at(ifd)?);
+    (0..count)
+        .map(|i| ifd + 2 + i * 12)
+        .find(|entry| u16_at(*entry) == Some(EXIF_ORIENTATION_TAG))
+        .and_then(|entry| u16_at(entry + 8))
+        .filter(|orientation| (1..=8).contains(orientation))
+}
+
+// APP1 с единственным тегом Orientation
+fn orientation_segment(orientation: u16) -> Vec<u8> {
+    let mut payload = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+    payload.extend_from_slice(&EXIF_ORIENTATION_TAG.to_be_bytes());
+    // Тип SHORT, одно значение, затем смещение следующего IFD — его нет
+    payload.extend_from_slice(b"\0\x03\0\0\0\x01");
+    payload.extend_from_slice(&orientation.to_be_bytes());
+    payload.extend_from_slice(b"\0\0\0\0\0\0");
+    let mut segment = vec![0xFF, 0xE1];
+    segment.extend_from_slice(&(payload.len() as u16 + 2).to_be_bytes());
+    segment.extend_from_slice(&payload);
+    segment
+}
+
+fn strip_png(data: &[u8]) -> Result<Vec<u8>, ImagingError> {
+    let malformed = || ImagingError::Malformed("truncated PNG chunk");
+    let mut out = data.get(..8).ok_or_else(malformed)?.to_vec();
+    let mut pos = 8;
+    while pos < data.len() {
+        let header = data.get(pos..pos + 8).ok_or_else(malformed)?;
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        // Длина, тип, данные и CRC
+        let end = pos + 12 + len;
+        if end > data.len() {
+            return Err(malformed());
+        }
+        if !matches!(
+            &header[4..],
+            b"eXIf" | b"tEXt" | b"zTXt" | b"iTXt" | b"tIME"
+        ) {
+            out.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+    Ok(out)
+}
+
+fn strip_webp(data: &[u8]) -> Result<Vec<u8>, ImagingError> {
+    let malformed = || ImagingError::Malformed("truncated WebP chunk");
+    let mut out = data.get(..12).ok_or_else(malformed)?.to_vec();
+    let mut pos = 12;
+    while pos < data.len() {
+        let header = data.get(pos..pos + 8).ok_or_else(malformed)?;
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if pos + 8 + len > data.len() {
+            return Err(malformed());
+        }
+        // Чанки выравниваются до чётной длины; у последнего выравнивание иногда отсутствует
+        let end = (pos + 8 + len + len % 2).min(data.len());
+        match &header[..4] {
+            b"EXIF" | b"XMP " => {}
+            b"VP8X" if len > 0 => {
+                let flags = out.len() + 8;
+                out.extend_from_slice(&data[pos..end]);
+                out[flags] &= !(0x08 | 0x04);
+            }
+            _ => out.extend_from_slice(&data[pos..end]),
+        }
+        pos = end;
+    }
+    let riff_len = (out.len() - 8) as u32;
+    out[4..8].copy_from_slice(&riff_len.to_le_bytes());
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, RgbImage};
+
+    fn encoded(image: RgbImage, format: ImageFormat) -> Vec<u8> {
+        let mut out = Cursor::new(Vec::new());
+        DynamicImage::ImageRgb8(image)
+            .write_to(&mut out, format)
+            .unwrap();
+        out.into_inner()
+    }
+
+    fn sizes() -> Vec<DerivativeSize> {
+        vec![
+            DerivativeSize {
+                name: "thumbnail".into(),
+                width: 40,
+            },
+            DerivativeSize {
+                name: "large".into(),
+                width: 400,
+            },
+            DerivativeSize {
+                name: "xlarge".into(),
+                width: 800,
+            },
+        ]
+    }
+
+    #[test]
+    fn strips_jpeg_metadata_but_keeps_orientation() {
+        let jpeg = encoded(RgbImage::new(30, 20), ImageFormat::Jpeg);
+        // EXIF с ориентацией 6 (поворот на 90°) и условными данными GPS
+        let mut exif = orientation_segment(6);
+        exif.extend_from_slice(b"GPSLatitude");
+        let len = (exif.len() - 2) as u16;
+        exif[2..4].copy_from_slice(&len.to_be_bytes());
+        let mut data = jpeg[..2].to_vec();
+        data.extend_from_slice(&exif);
+        data.extend_from_slice(b"\xff\xfe\0\x09comment");
+        data.extend_from_slice(&jpeg[2..]);
+
+        let stripped = strip_metadata(&data, "image/jpeg").unwrap();
+        assert!(!stripped.windows(3).any(|w| w == b"GPS"));
+        assert!(!stripped.windows(7).any(|w| w == b"comment"));
+        let orientation = orientation_segment(6);
+        assert_eq!(&stripped[2..2 + orientation.len()], orientation.as_slice());
+        assert!(stripped.ends_with(&jpeg[2..]));
+        assert_eq!(exif_orientation(&orientation[10..]), Some(6));
+
+        // Копии строятся уже повёрнутыми
+        let rendered = render_derivatives(&stripped, "image/jpeg", &sizes()).unwrap();
+        assert_eq!((rendered.width, rendered.height), (20, 30));
+        let thumbnail = &rendered.renditions[0];
+        assert_eq!((thumbnail.width, thumbnail.height), (20, 30));
+    }
+
+    #[test]
+    fn strips_png_text_chunks() {
+        let png = encoded(RgbImage::new(4, 4), ImageFormat::Png);
+        let mut data = png[..33].to_vec();
+        data.extend_from_slice(b"\0\0\0\x07tEXtAuthor\0\0\0\0\0");
+        data.extend_from_slice(&png[33..]);
+
+        let stripped = strip_metadata(&data, "image/png").unwrap();
+        assert_eq!(stripped, png);
+        assert!(strip_metadata(&data[..40], "image/png").is_err());
+    }
+
+    #[test]
+    fn renders_webp_and_fallback_without_upscaling() {
+        let png = encoded(RgbImage::new(100, 50), ImageFormat::Png);
+        let rendered = render_derivatives(&png, "image/png", &sizes()).unwrap();
+        let summary: Vec<_> = rendered
+            .renditions
+            .iter()
+            .map(|r| (r.name.as_str(), r.mime_type, r.width, r.height))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("thumbnail", "image/webp", 40, 20),
+                ("thumbnail", "image/png", 40, 20),
+                ("large", "image/webp", 100, 50),
+                ("large", "image/png", 100, 50),
+            ]
+        );
+        let webp = image::load_from_memory(&rendered.renditions[0].data).unwrap();
+        assert_eq!((webp.width(), webp.height()), (40, 20));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod config;
 pub mod jwt;
 pub mod database;
-pub mod logging;
\ No newline at end of file
+pub mod logging;
+pub mod storage;
+pub mod imaging;
\ No newline at end of file
@@ -8,17 +8,39 @@ mod presentation;
 use actix_cors::Cors;
 use actix_web::middleware::{DefaultHeaders, Logger};
 use actix_web::{App, HttpServer, web};
+use application::analytics_service::AnalyticsService;
 use application::auth_service::AuthService;
 use application::blog_service::PostService;
+use application::comment_service::CommentService;
+use application::follow_service::FollowService;
+use application::media_processor::{QUEUE_CAPACITY, run_media_processor};
+use application::media_service::MediaService;
+use application::publish_scheduler::run_publish_scheduler;
+use application::series_service::SeriesService;
+use application::trash_purger::run_trash_purger;
+use application::tag_service::TagService;
+use application::view_flusher::run_view_flusher;
+use data::analytics_repository::PostgresAnalyticsRepository;
+use data::comment_repository::PostgresCommentRepository;
+use data::media_repository::PostgresMediaRepository;
 use data::post_repository::PostgresPostRepository;
+use data::series_repository::PostgresSeriesRepository;
+use data::tag_repository::PostgresTagRepository;
 use data::user_repository::PostgresUserRepository;
 use infrastructure::config::AppConfig;
 use infrastructure::database::{create_pool, run_migrations};
 use infrastructure::jwt::JwtKeys;
 use infrastructure::logging::init_logging;
-use presentation::http::{auth_handlers, help_handlers, posts_hendlers};
-use presentation::middleware::{JwtAuthMiddleware, RequestIdMiddleware, TimingMiddleware};
+use infrastructure::storage::build_storage;
+use presentation::http::{
+    auth_handlers, feed_handlers, feeds_handlers, help_handlers, me_handlers, media_handlers,
+    posts_hendlers, series_handlers, tags_handlers, users_handlers,
+};
+use presentation::middleware::{
+    HttpCacheMiddleware, JwtAuthMiddleware, RequestIdMiddleware, TimingMiddleware,
+};
 use std::sync::Arc;
+use std::time::Duration;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -34,13 +56,56 @@ async fn main() -> std::io::Result<()> {
         .expect("failed to run migrations");
 
     let user_repo = Arc::new(PostgresUserRepository::new(pool.clone()));
-    let post_repo = Arc::new(PostgresPostRepository::new(pool.clone()));
+    let post_repo = Arc::new(PostgresPostRepository::new(
+        pool.clone(),
+        config.search_language.clone(),
+    ));
 
     let auth_service = Arc::new(AuthService::new(
         Arc::clone(&user_repo),
         JwtKeys::new(config.jwt_secret.clone()),
     ));
-    let post_service = Arc::new(PostService::new(post_repo));
+    let follow_service = Arc::new(FollowService::new(Arc::clone(&user_repo)));
+    let post_service = Arc::new(
+        PostService::new(post_repo.clone())
+            .with_trash_retention(chrono::Duration::days(config.trash_retention_days)),
+    );
+    let tag_repo = Arc::new(PostgresTagRepository::new(pool.clone()));
+    let tag_service = Arc::new(TagService::new(tag_repo));
+    let comment_repo = Arc::new(PostgresCommentRepository::new(pool.clone()));
+    let comment_service = Arc::new(CommentService::new(comment_repo, post_repo.clone()));
+    let analytics_service = Arc::new(AnalyticsService::new(
+        Arc::new(PostgresAnalyticsRepository::new(pool.clone())),
+        post_repo,
+        config.view_hash_secret.clone(),
+    ));
+    let series_service = Arc::new(SeriesService::new(Arc::new(PostgresSeriesRepository::new(
+        pool.clone(),
+    ))));
+    let (media_jobs, media_queue) = tokio::sync::mpsc::channel(QUEUE_CAPACITY);
+    let media_service = Arc::new(
+        MediaService::new(
+            Arc::new(PostgresMediaRepository::new(pool.clone())),
+            build_storage(&config.media_storage),
+            config.media_url.clone(),
+        )
+        .with_limits(config.media_max_file_bytes, config.media_quota_bytes)
+        .with_derivatives(config.media_derivatives.clone(), media_jobs),
+    );
+
+    // === Переиндексация постов после смены SEARCH_LANGUAGE, до приёма запросов ===
+    match post_service.reindex_search().await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(count, "reindexed posts for the new search language"),
+        Err(e) => tracing::error!("failed to reindex posts for search: {}", e),
+    }
+
+    // === Slug'и из заголовков для постов, созданных до появления slug'ов ===
+    match post_service.backfill_slugs().await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(count, "generated slugs for existing posts"),
+        Err(e) => tracing::error!("failed to generate slugs for existing posts: {}", e),
+    }
 
     // === HTTP-сервер ===
     let http_config = Arc::new(config.clone());
@@ -48,6 +113,12 @@ async fn main() -> std::io::Result<()> {
     let http_auth_service = auth_service.clone();
     let grpc_post_service = post_service.clone();
     let grpc_auth_service = auth_service.clone();
+    let http_tag_service = tag_service.clone();
+    let http_comment_service = comment_service.clone();
+    let http_follow_service = follow_service.clone();
+    let http_media_service = media_service.clone();
+    let http_series_service = series_service.clone();
+    let http_analytics_service = analytics_service.clone();
 
     let http_config_clone = Arc::clone(&http_config);
     let http_handle = HttpServer::new(move || {
@@ -60,12 +131,60 @@ async fn main() -> std::io::Result<()> {
             .wrap(cors)
             .app_data(web::Data::from(http_auth_service.clone()))
             .app_data(web::Data::from(http_post_service.clone()))
+            .app_data(web::Data::from(http_tag_service.clone()))
+            .app_data(web::Data::from(http_comment_service.clone()))
+            .app_data(web::Data::from(http_follow_service.clone()))
+            .app_data(web::Data::from(http_media_service.clone()))
+            .app_data(web::Data::from(http_series_service.clone()))
+            .app_data(web::Data::from(http_analytics_service.clone()))
+            .app_data(web::Data::from(http_config_clone.clone()))
+            // Ленты подписки читаются агрегаторами без токена
+            .service(
+                feeds_handlers::scope().wrap(HttpCacheMiddleware::new(&http_config_clone)),
+            )
+            .service(
+                media_handlers::public_scope()
+                    .wrap(HttpCacheMiddleware::new(&http_config_clone)),
+            )
             .service(
                 web::scope("/api")
                     .service(help_handlers::scope())
                     .service(auth_handlers::scope())
+                    // Публичное чтение постов, тегов и серий доступно без токена, остальное — только с ним
                     .service(
                         posts_hendlers::scope()
+                            .wrap(
+                                JwtAuthMiddleware::new(http_auth_service.keys().clone())
+                                    .allow_anonymous_reads(posts_hendlers::ANONYMOUS_READS),
+                            )
+                            .wrap(HttpCacheMiddleware::new(&http_config_clone)),
+                    )
+                    .service(
+                        tags_handlers::scope().wrap(
+                            JwtAuthMiddleware::new(http_auth_service.keys().clone())
+                                .allow_anonymous_reads(tags_handlers::ANONYMOUS_READS),
+                        ),
+                    )
+                    .service(
+                        series_handlers::scope().wrap(
+                            JwtAuthMiddleware::new(http_auth_service.keys().clone())
+                                .allow_anonymous_reads(series_handlers::ANONYMOUS_READS),
+                        ),
+                    )
+                    .service(
+                        users_handlers::scope()
+                            .wrap(JwtAuthMiddleware::new(http_auth_service.keys().clone())),
+                    )
+                    .service(
+                        feed_handlers::scope()
+                            .wrap(JwtAuthMiddleware::new(http_auth_service.keys().clone())),
+                    )
+                    .service(
+                        me_handlers::scope()
+                            .wrap(JwtAuthMiddleware::new(http_auth_service.keys().clone())),
+                    )
+                    .service(
+                        media_handlers::scope()
                             .wrap(JwtAuthMiddleware::new(http_auth_service.keys().clone())),
                     ),
             )
@@ -75,10 +194,16 @@ async fn main() -> std::io::Result<()> {
 
     fn build_cors(config: &AppConfig) -> Cors {
         let mut cors = Cors::default()
-            .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
+            .allowed_methods(vec!["GET", "POST", "PUT", "PATCH", "DELETE"])
             .allowed_headers(vec![
                 actix_web::http::header::CONTENT_TYPE,
                 actix_web::http::header::AUTHORIZATION,
+                actix_web::http::header::IF_MATCH,
+                actix_web::http::header::IF_NONE_MATCH,
+            ])
+            .expose_headers(vec![
+                actix_web::http::header::ETAG,
+                actix_web::http::header::LINK,
             ])
             .supports_credentials()
             .max_age(3600);
@@ -93,17 +218,86 @@ async fn main() -> std::io::Result<()> {
     let grpc_addr = format!("{}:{}", config.host, config.grpc_port);
 
     let grpc_handle = tokio::spawn(async move {
-        let grpc_impl = presentation::grpc::PostGrpcService::new(grpc_post_service);
-        let tonic_svc = crate::post_service_server::PostServiceServer::new(grpc_impl);
+        // Все gRPC-сервисы принимают тот же JWT, что и HTTP
+        let grpc_impl = presentation::grpc::PostGrpcService::new(grpc_post_service.clone());
+        let tonic_svc = presentation::grpc::JwtAuth::new(
+            crate::post_service_server::PostServiceServer::new(grpc_impl),
+            grpc_auth_service.clone(),
+        );
+        let grpc_tags = presentation::grpc::TagGrpcService::new(grpc_post_service, tag_service);
+        let tags_svc = presentation::grpc::JwtAuth::new(
+            crate::tag_service_server::TagServiceServer::new(grpc_tags),
+            grpc_auth_service.clone(),
+        );
+        let grpc_comments = presentation::grpc::CommentGrpcService::new(comment_service);
+        let comments_svc = presentation::grpc::JwtAuth::new(
+            crate::comment_service_server::CommentServiceServer::new(grpc_comments),
+            grpc_auth_service,
+        );
         tonic::transport::Server::builder()
             .add_service(tonic_svc)
+            .add_service(tags_svc)
+            .add_service(comments_svc)
             .serve(grpc_addr.parse().unwrap())
             .await
     });
 
+    // === Рендеринг Markdown для постов, созданных до появления content_html ===
+    let render_service = post_service.clone();
+    tokio::spawn(async move {
+        match render_service.render_missing_html().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!(count, "rendered markdown for existing posts"),
+            Err(e) => tracing::error!("failed to render markdown for existing posts: {}", e),
+        }
+    });
+
+    // === Уменьшенные копии картинок ===
+    let processor_handle = tokio::spawn(run_media_processor(
+        media_service.clone(),
+        media_queue,
+        config.media_workers,
+    ));
+    tokio::spawn(async move {
+        match media_service.resume_processing().await {
+            Ok(0) => {}
+            Ok(count) => tracing::info!(count, "resumed processing of uploaded media"),
+            Err(e) => tracing::error!("failed to resume media processing: {}", e),
+        }
+    });
+
+    // === Отложенная публикация ===
+    let scheduler_handle = tokio::spawn(run_publish_scheduler(
+        post_service.clone(),
+        Duration::from_secs(config.publish_interval_secs),
+    ));
+
+    // === Очистка корзины ===
+    let purger_handle = tokio::spawn(run_trash_purger(
+        post_service.clone(),
+        Duration::from_secs(config.purge_interval_secs),
+    ));
+
+    // === Запись просмотров постов ===
+    let flusher_handle = tokio::spawn(run_view_flusher(
+        analytics_service.clone(),
+        Duration::from_secs(config.view_flush_interval_secs),
+    ));
+
     tokio::select! {
         _ = http_handle => {},
         _ = grpc_handle => {},
+        _ = scheduler_handle => {},
+        _ = purger_handle => {},
+        _ = processor_handle => {},
+        _ = flusher_handle => {},
+    }
+
+    // Просмотры, накопленные после последнего сброса, записываются перед выходом
+    match analytics_service.flush_views().await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!(count, "pending post views flushed on shutdown"),
+        Err(e) => tracing::error!("failed to flush post views on shutdown: {}", e),
     }
 
     Ok(())
@@ -1,5 +1,10 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::domain::collaborator::PostRole;
+use crate::domain::error::DomainError;
+use crate::domain::post::{CommentMode, PostPatch, PostStatus};
+use crate::domain::post_query::PostListParams;
 
 #[derive(Debug, Deserialize)]
 pub struct RegisterRequest {
@@ -30,4 +35,160 @@ pub struct TokenResponse {
 pub struct PostRequest {
     pub title: String,
     pub content: String,
+    // Учитывается только при создании: draft или published (по умолчанию)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<PostStatus>,
+    // При обновлении отсутствие поля оставляет теги без изменений
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
+// Тело PATCH /api/post/{id} в формате JSON Merge Patch (RFC 7396):
+// отсутствующее поле не меняется, null удаляет значение
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PostMergePatch {
+    #[serde(default, deserialize_with = "present")]
+    pub title: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub content: Option<Option<String>>,
+    #[serde(default, deserialize_with = "present")]
+    pub tags: Option<Option<Vec<String>>>,
+}
+
+impl TryFrom<PostMergePatch> for PostPatch {
+    type Error = DomainError;
+
+    // Заголовок и текст обязательны, а удаление тегов означает пустой список
+    fn try_from(patch: PostMergePatch) -> Result<Self, Self::Error> {
+        let required = |value: Option<Option<String>>, field: &str| {
+            value
+                .map(|value| {
+                    value.ok_or_else(|| {
+                        DomainError::Validation(format!("{} cannot be removed", field))
+                    })
+                })
+                .transpose()
+        };
+        Ok(PostPatch {
+            title: required(patch.title, "title")?,
+            content: required(patch.content, "content")?,
+            tags: patch.tags.map(Option::unwrap_or_default),
+            content_html: None,
+        })
+    }
+}
+
+// Отличает отсутствующее поле (None) от явного null (Some(None))
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PostsQuery {
+    pub author_id: Option<i64>,
+    pub status: Option<String>,
+    pub tag: Option<String>,
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    pub sort: Option<String>,
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+impl From<PostsQuery> for PostListParams {
+    fn from(query: PostsQuery) -> Self {
+        Self {
+            viewer_id: None,
+            author_id: query.author_id,
+            status: query.status,
+            tag: query.tag,
+            followed_by: None,
+            created_after: query.created_after,
+            created_before: query.created_before,
+            sort: query.sort,
+            cursor: query.cursor,
+            limit: query.limit,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SearchQuery {
+    pub q: String,
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PageQuery {
+    pub cursor: Option<String>,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduleRequest {
+    pub publish_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RevisionDiffQuery {
+    pub from: i32,
+    pub to: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentRequest {
+    pub content: String,
+    // Ответ на комментарий; отсутствует у комментария верхнего уровня
+    #[serde(default)]
+    pub parent_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentUpdateRequest {
+    pub content: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ModerationRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentModeRequest {
+    pub mode: CommentMode,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StatsQuery {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CollaboratorRequest {
+    pub role: PostRole,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeriesRequest {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeriesPostsRequest {
+    // Части серии по порядку
+    pub post_ids: Vec<i64>,
 }
@@ -0,0 +1,193 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use crate::application::auth_service::AuthService;
+use crate::data::user_repository::UserRepository;
+use crate::domain::error::PostError;
+use crate::presentation::auth::AuthenticatedUser;
+use tonic::body::BoxBody;
+use tonic::codegen::http::{self, HeaderValue};
+use tonic::codegen::{BoxFuture, Context, Poll, Service};
+use tonic::server::NamedService;
+use tonic::{Request, Status};
+
+// Проверяет JWT так же, как HTTP API: подпись токена и что пользователь ещё существует.
+// Пользователь кладётся в расширения запроса. Запрос без токена проходит анонимно,
+// с неверным токеном или токеном удалённого пользователя — отклоняется
+pub struct JwtAuth<S, R: UserRepository + 'static> {
+    inner: S,
+    auth: Arc<AuthService<R>>,
+}
+
+impl<S, R: UserRepository + 'static> JwtAuth<S, R> {
+    pub fn new(inner: S, auth: Arc<AuthService<R>>) -> Self {
+        Self { inner, auth }
+    }
+}
+
+impl<S: Clone, R: UserRepository + 'static> Clone for JwtAuth<S, R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+impl<S: NamedService, R: UserRepository + 'static> NamedService for JwtAuth<S, R> {
+    const NAME: &'static str = S::NAME;
+}
+
+impl<S, R, B> Service<http::Request<B>> for JwtAuth<S, R>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>, Error = Infallible>
+        + Clone
+        + Send
+        + 'static,
+    S::Future: Send + 'static,
+    R: UserRepository + 'static,
+    B: Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        // Готовым к вызову считается сервис, у которого вызывали poll_ready, поэтому
+        // в future уходит он, а на его место встаёт клон
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let auth = self.auth.clone();
+        Box::pin(async move {
+            let header = request.headers().get(http::header::AUTHORIZATION);
+            match authenticate(&auth, header).await {
+                Ok(Some(user)) => {
+                    request.extensions_mut().insert(user);
+                }
+                Ok(None) => {}
+                Err(message) => return Ok(Status::unauthenticated(message).to_http()),
+            }
+            inner.call(request).await
+        })
+    }
+}
+
+async fn authenticate<R: UserRepository + 'static>(
+    auth: &AuthService<R>,
+    header: Option<&HeaderValue>,
+) -> Result<Option<AuthenticatedUser>, &'static str> {
+    let Some(value) = header else {
+        return Ok(None);
+    };
+    let token = value
+        .to_str()
+        .ok()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or("invalid authorization header")?;
+    let claims = auth
+        .keys()
+        .verify_token(token)
+        .map_err(|_| "invalid token")?;
+    let user_id: i64 = claims.sub.parse().map_err(|_| "invalid token")?;
+    let user = auth.get_user(user_id).await.map_err(|_| "user not found")?;
+    Ok(Some(AuthenticatedUser {
+        id: user.id,
+        email: user.email,
+    }))
+}
+
+// Пользователь из токена; без токена — Unauthorized
+pub(crate) fn current_user<T>(request: &Request<T>) -> Result<AuthenticatedUser, PostError> {
+    request
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .cloned()
+        .ok_or(PostError::Unauthorized)
+}
+
+// id пользователя из токена, если он передан
+pub(crate) fn viewer_id<T>(request: &Request<T>) -> Option<i64> {
+    request
+        .extensions()
+        .get::<AuthenticatedUser>()
+        .map(|user| user.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::memory::MemoryUserRepository;
+    use crate::infrastructure::jwt::JwtKeys;
+
+    async fn auth_service() -> (Arc<MemoryUserRepository>, AuthService<MemoryUserRepository>) {
+        let repo = Arc::new(MemoryUserRepository::default());
+        let auth = AuthService::new(repo.clone(), JwtKeys::new("secret".into()));
+        auth.register(
+            "Reader@Example.com".into(),
+            "reader".into(),
+            "password".into(),
+        )
+        .await
+        .unwrap();
+        (repo, auth)
+    }
+
+    async fn check(
+        auth: &AuthService<MemoryUserRepository>,
+        header: &str,
+    ) -> Result<Option<AuthenticatedUser>, &'static str> {
+        authenticate(auth, Some(&HeaderValue::from_str(header).unwrap())).await
+    }
+
+    #[tokio::test]
+    async fn takes_user_from_token() {
+        let (_, auth) = auth_service().await;
+        let token = auth.keys().generate_token(1).unwrap();
+        let user = check(&auth, &format!("Bearer {}", token))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(user.id, 1);
+        assert_eq!(user.email, "reader@example.com");
+
+        let mut request = Request::new(());
+        request.extensions_mut().insert(user);
+        assert_eq!(viewer_id(&request), Some(1));
+        assert_eq!(current_user(&request).unwrap().id, 1);
+
+        assert!(authenticate(&auth, None).await.unwrap().is_none());
+        let anonymous = Request::new(());
+        assert_eq!(viewer_id(&anonymous), None);
+        assert!(matches!(
+            current_user(&anonymous),
+            Err(PostError::Unauthorized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_tokens() {
+        let (repo, auth) = auth_service().await;
+        let foreign = JwtKeys::new("other".into()).generate_token(1).unwrap();
+        for header in [
+            format!("Bearer {}", foreign),
+            "Bearer garbage".to_string(),
+            "Basic dXNlcjpwYXNz".to_string(),
+        ] {
+            assert!(check(&auth, &header).await.is_err());
+        }
+
+        // Токен удалённого пользователя перестаёт действовать сразу, а не по истечении
+        let token = auth.keys().generate_token(1).unwrap();
+        repo.delete(1);
+        assert_eq!(
+            check(&auth, &format!("Bearer {}", token))
+                .await
+                .unwrap_err(),
+            "user not found"
+        );
+    }
+}
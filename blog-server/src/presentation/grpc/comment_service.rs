@@ -0,0 +1,166 @@
+use crate::application::comment_service::CommentService;
+use crate::comment_service_server::CommentService as GrpcCommentService;
+use crate::data::comment_repository::CommentRepository;
+use crate::data::post_repository::PostRepository;
+use crate::domain::comment::{Comment, CommentStatus, CommentThread};
+use crate::presentation::grpc::auth::{current_user, viewer_id};
+use crate::presentation::grpc::post_service::map_error;
+use crate::{
+    Comment as GrpcComment, CommentThread as GrpcCommentThread, CreateCommentRequest,
+    CreateCommentResponse, DeleteCommentRequest, DeleteCommentResponse, ListCommentsRequest,
+    ListCommentsResponse, ListPendingCommentsRequest, ListPendingCommentsResponse,
+    ModerateCommentRequest, ModerateCommentResponse, UpdateCommentRequest, UpdateCommentResponse,
+};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+// Обёртка над CommentService для gRPC
+pub struct CommentGrpcService<C, P>
+where
+    C: CommentRepository + 'static,
+    P: PostRepository + 'static,
+{
+    service: Arc<CommentService<C, P>>,
+}
+
+impl<C, P> CommentGrpcService<C, P>
+where
+    C: CommentRepository + 'static,
+    P: PostRepository + 'static,
+{
+    pub fn new(service: Arc<CommentService<C, P>>) -> Self {
+        Self { service }
+    }
+}
+
+fn comment_to_grpc(comment: Comment) -> GrpcComment {
+    GrpcComment {
+        id: comment.id,
+        post_id: comment.post_id,
+        author_id: comment.author_id,
+        parent_id: comment.parent_id.unwrap_or_default(),
+        depth: comment.depth,
+        content: comment.content,
+        deleted: comment.deleted,
+        created_at: comment.created_at.to_rfc3339(),
+        edited_at: comment
+            .edited_at
+            .map(|ts| ts.to_rfc3339())
+            .unwrap_or_default(),
+        status: comment.status.as_str().to_string(),
+        moderation_reason: comment.moderation_reason.unwrap_or_default(),
+    }
+}
+
+fn thread_to_grpc(thread: CommentThread) -> GrpcCommentThread {
+    GrpcCommentThread {
+        comment: Some(comment_to_grpc(thread.comment)),
+        replies: thread.replies.into_iter().map(thread_to_grpc).collect(),
+    }
+}
+
+#[tonic::async_trait]
+impl<C, P> GrpcCommentService for CommentGrpcService<C, P>
+where
+    C: CommentRepository + Send + Sync + 'static,
+    P: PostRepository + Send + Sync + 'static,
+{
+    async fn create_comment(
+        &self,
+        request: Request<CreateCommentRequest>,
+    ) -> Result<Response<CreateCommentResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let comment = self
+            .service
+            .add_comment(
+                req.post_id,
+                Some(req.parent_id).filter(|&id| id != 0),
+                req.content,
+                user,
+            )
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(CreateCommentResponse {
+            comment: Some(comment_to_grpc(comment)),
+        }))
+    }
+
+    async fn list_comments(
+        &self,
+        request: Request<ListCommentsRequest>,
+    ) -> Result<Response<ListCommentsResponse>, Status> {
+        let viewer_id = viewer_id(&request);
+        let req = request.into_inner();
+        let threads = self
+            .service
+            .list_comments(req.post_id, viewer_id)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(ListCommentsResponse {
+            threads: threads.into_iter().map(thread_to_grpc).collect(),
+        }))
+    }
+
+    async fn update_comment(
+        &self,
+        request: Request<UpdateCommentRequest>,
+    ) -> Result<Response<UpdateCommentResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let comment = self
+            .service
+            .update_comment(req.post_id, req.id, req.content, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(UpdateCommentResponse {
+            comment: Some(comment_to_grpc(comment)),
+        }))
+    }
+
+    async fn delete_comment(
+        &self,
+        request: Request<DeleteCommentRequest>,
+    ) -> Result<Response<DeleteCommentResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        self.service
+            .delete_comment(req.post_id, req.id, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(DeleteCommentResponse {}))
+    }
+
+    async fn list_pending_comments(
+        &self,
+        request: Request<ListPendingCommentsRequest>,
+    ) -> Result<Response<ListPendingCommentsResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let comments = self
+            .service
+            .list_pending(req.post_id, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(ListPendingCommentsResponse {
+            comments: comments.into_iter().map(comment_to_grpc).collect(),
+        }))
+    }
+
+    async fn moderate_comment(
+        &self,
+        request: Request<ModerateCommentRequest>,
+    ) -> Result<Response<ModerateCommentResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let decision = CommentStatus::parse(&req.decision).map_err(|e| map_error(e.into()))?;
+        let comment = self
+            .service
+            .moderate_comment(req.post_id, req.id, decision, Some(req.reason), user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(ModerateCommentResponse {
+            comment: Some(comment_to_grpc(comment)),
+        }))
+    }
+}
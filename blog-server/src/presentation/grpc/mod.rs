@@ -1,3 +1,9 @@
+pub mod auth;
 pub mod auth_service;
+pub mod comment_service;
 pub mod post_service;
+pub mod tag_service;
+pub use auth::JwtAuth;
+pub use comment_service::CommentGrpcService;
 pub use post_service::PostGrpcService;
+pub use tag_service::TagGrpcService;
\ No newline at end of file
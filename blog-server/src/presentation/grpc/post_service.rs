@@ -1,12 +1,30 @@
 use crate::application::blog_service::PostService;
 use crate::data::post_repository::PostRepository;
+use crate::domain::collaborator::{Collaborator, PostRole};
 use crate::domain::error::PostError;
-use crate::domain::post::NewPost;
+use crate::domain::post::{CommentMode, NewPost, PostPatch, PostStatus, SlugLookup};
+use crate::domain::post_query::{PostListParams, parse_timestamp};
+use crate::domain::reaction::ReactionKind;
+use crate::domain::revision::{DiffLine, PostRevision};
+use crate::domain::series::PostLink;
 use crate::post_service_server::PostService as GrpcPostService;
+use crate::presentation::grpc::auth::{current_user, viewer_id};
 use crate::{
-    CreatePostRequest, CreatePostResponse, DeletePostRequest, DeletePostResponse, GetPostRequest,
-    GetPostResponse, GetPostsRequest, GetPostsResponse, Post as GrpcPost, UpdatePostRequest,
-    UpdatePostResponse,
+    ArchivePostRequest, ArchivePostResponse, Collaborator as GrpcCollaborator, CreatePostRequest,
+    CreatePostResponse, DeletePostRequest, DeletePostResponse, DiffLine as GrpcDiffLine,
+    DiffPostRevisionsRequest, DiffPostRevisionsResponse, GetFeedRequest, GetPostBySlugRequest,
+    GetPostBySlugResponse, GetPostRequest, GetPostResponse, GetPostRevisionRequest,
+    GetPostRevisionResponse, GetPostsRequest, GetPostsResponse, ListCollaboratorsRequest,
+    ListCollaboratorsResponse, ListPostRevisionsRequest, ListPostRevisionsResponse,
+    ListTrashRequest, ListTrashResponse, PatchPostRequest, PatchPostResponse, Post as GrpcPost,
+    PostLink as GrpcPostLink, PostRevision as GrpcPostRevision, PublishPostRequest,
+    PublishPostResponse, Reaction as GrpcReaction, ReactionRequest, ReactionResponse,
+    RemoveCollaboratorRequest, RemoveCollaboratorResponse, RestorePostRequest, RestorePostResponse,
+    RestorePostRevisionRequest, RestorePostRevisionResponse, SchedulePostRequest,
+    SchedulePostResponse, SearchHit, SearchPostsRequest, SearchPostsResponse,
+    SeriesNavigation as GrpcSeriesNavigation, SetCollaboratorRequest, SetCollaboratorResponse,
+    SetCommentModeRequest, SetCommentModeResponse, TrashedPost as GrpcTrashedPost,
+    UnpublishPostRequest, UnpublishPostResponse, UpdatePostRequest, UpdatePostResponse,
 };
 use std::sync::Arc;
 use tonic::{Request, Response, Status};
@@ -29,26 +47,164 @@ where
 }
 
 // Конвертеры
-fn domain_to_grpc(post: crate::domain::post::Post) -> GrpcPost {
+pub(crate) fn domain_to_grpc(post: crate::domain::post::Post) -> GrpcPost {
     GrpcPost {
         id: post.id,
         title: post.title,
         content: post.content,
         author_id: post.author_id,
         created_at: post.created_at.to_rfc3339(), // ISO строка
+        updated_at: post.updated_at.to_rfc3339(),
+        status: post.status.as_str().to_string(),
+        published_at: post
+            .published_at
+            .map(|ts| ts.to_rfc3339())
+            .unwrap_or_default(),
+        publish_at: post
+            .publish_at
+            .map(|ts| ts.to_rfc3339())
+            .unwrap_or_default(),
+        slug: post.slug,
+        content_html: post.content_html,
+        tags: post.tags,
+        comment_mode: post.comment_mode.as_str().to_string(),
+        reactions: post
+            .reactions
+            .into_iter()
+            .map(|reaction| GrpcReaction {
+                kind: reaction.kind.as_str().to_string(),
+                emoji: reaction.emoji,
+                count: reaction.count,
+                reacted: reaction.reacted,
+            })
+            .collect(),
+        deleted_at: post
+            .deleted_at
+            .map(|ts| ts.to_rfc3339())
+            .unwrap_or_default(),
+        version: post.version,
+        series: post.series.map(|series| GrpcSeriesNavigation {
+            id: series.id,
+            title: series.title,
+            position: series.position,
+            previous: series.previous.map(link_to_grpc),
+            next: series.next.map(link_to_grpc),
+        }),
     }
 }
 
-fn grpc_to_domain_new_post(req: &CreatePostRequest) -> NewPost {
-    NewPost::new(req.title.clone(), req.content.clone(), req.author_id)
+fn link_to_grpc(link: PostLink) -> GrpcPostLink {
+    GrpcPostLink {
+        id: link.id,
+        slug: link.slug,
+        title: link.title,
+    }
+}
+
+fn grpc_to_domain_new_post(req: &CreatePostRequest, author_id: i64) -> Result<NewPost, PostError> {
+    let status = if req.status.is_empty() {
+        PostStatus::Published
+    } else {
+        PostStatus::parse(&req.status)?
+    };
+    Ok(
+        NewPost::new(req.title.clone(), req.content.clone(), author_id)
+            .with_status(status)
+            .with_tags(Some(req.tags.clone())),
+    )
+}
+
+fn revision_to_grpc(revision: PostRevision) -> GrpcPostRevision {
+    GrpcPostRevision {
+        post_id: revision.post_id,
+        revision: revision.revision,
+        title: revision.title,
+        content: revision.content,
+        editor_id: revision.editor_id,
+        created_at: revision.created_at.to_rfc3339(),
+    }
+}
+
+fn collaborator_to_grpc(collaborator: Collaborator) -> GrpcCollaborator {
+    GrpcCollaborator {
+        user_id: collaborator.user_id,
+        username: collaborator.username,
+        role: collaborator.role.as_str().to_string(),
+        invited_by: collaborator.invited_by.unwrap_or_default(),
+        created_at: collaborator.created_at.to_rfc3339(),
+    }
+}
+
+fn diff_to_grpc(lines: Vec<DiffLine>) -> Vec<GrpcDiffLine> {
+    lines
+        .into_iter()
+        .map(|line| GrpcDiffLine {
+            op: line.op.as_str().to_string(),
+            text: line.text,
+        })
+        .collect()
+}
+
+fn grpc_to_list_params(req: GetPostsRequest, viewer_id: Option<i64>) -> PostListParams {
+    let non_empty = |s: String| Some(s).filter(|s| !s.is_empty());
+    PostListParams {
+        viewer_id,
+        author_id: Some(req.author_id).filter(|&id| id != 0),
+        status: non_empty(req.status),
+        tag: non_empty(req.tag),
+        followed_by: None,
+        created_after: non_empty(req.created_after),
+        created_before: non_empty(req.created_before),
+        sort: non_empty(req.sort),
+        cursor: non_empty(req.page_token),
+        limit: u32::try_from(req.page_size).ok().filter(|&n| n > 0),
+    }
+}
+
+// Переносит из post только поля, перечисленные в маске
+fn patch_from_mask(
+    post: GrpcPost,
+    mask: Option<prost_types::FieldMask>,
+) -> Result<PostPatch, PostError> {
+    let paths = mask.map(|mask| mask.paths).unwrap_or_default();
+    if paths.is_empty() {
+        return Err(PostError::Validation("update_mask is empty".into()));
+    }
+    let mut patch = PostPatch::default();
+    for path in paths {
+        match path.as_str() {
+            "title" => patch.title = Some(post.title.clone()),
+            "content" => patch.content = Some(post.content.clone()),
+            "tags" => patch.tags = Some(post.tags.clone()),
+            other => {
+                return Err(PostError::Validation(format!(
+                    "unsupported update_mask path: {}",
+                    other
+                )));
+            }
+        }
+    }
+    Ok(patch)
+}
+
+// 0 в expected_version — без проверки версии
+fn expected_versions(version: i32) -> Option<Vec<i32>> {
+    (version != 0).then(|| vec![version])
 }
 
 // Маппинг ошибок
-fn map_error(e: PostError) -> Status {
+pub(crate) fn map_error(e: PostError) -> Status {
     match e {
         PostError::PostNotFound(_) => Status::not_found(e.to_string()),
-        PostError::Unauthorized | PostError::Forbidden => Status::permission_denied(e.to_string()),
-        PostError::Validation(_) => Status::invalid_argument(e.to_string()),
+        PostError::Unauthorized => Status::unauthenticated(e.to_string()),
+        PostError::Forbidden => Status::permission_denied(e.to_string()),
+        PostError::Validation(_) | PostError::UnsupportedMediaType(_) => {
+            Status::invalid_argument(e.to_string())
+        }
+        PostError::InvalidState(_)
+        | PostError::PreconditionFailed(_)
+        | PostError::PreconditionRequired(_) => Status::failed_precondition(e.to_string()),
+        PostError::PayloadTooLarge(_) => Status::resource_exhausted(e.to_string()),
         PostError::Internal(_) => Status::internal(e.to_string()),
     }
 }
@@ -62,11 +218,18 @@ where
         &self,
         request: Request<CreatePostRequest>,
     ) -> Result<Response<CreatePostResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
         let req = request.into_inner();
-        let new_post = grpc_to_domain_new_post(&req);
+        let new_post = grpc_to_domain_new_post(&req, user.id).map_err(map_error)?;
         let post = self
             .service
-            .create_post(new_post.title, new_post.content, new_post.author_id)
+            .create_post(
+                new_post.title,
+                new_post.content,
+                new_post.status,
+                new_post.tags.unwrap_or_default(),
+                new_post.author_id,
+            )
             .await
             .map_err(map_error)?;
         Ok(Response::new(CreatePostResponse {
@@ -76,36 +239,96 @@ where
 
     async fn get_posts(
         &self,
-        _request: Request<GetPostsRequest>,
+        request: Request<GetPostsRequest>,
     ) -> Result<Response<GetPostsResponse>, Status> {
-        let posts = self.service.get_posts().await.map_err(map_error)?;
-        let grpc_posts = posts.into_iter().map(domain_to_grpc).collect();
-        Ok(Response::new(GetPostsResponse { posts: grpc_posts }))
+        let viewer_id = viewer_id(&request);
+        let req = request.into_inner();
+        let page = self
+            .service
+            .get_posts(grpc_to_list_params(req, viewer_id))
+            .await
+            .map_err(map_error)?;
+        let grpc_posts = page.posts.into_iter().map(domain_to_grpc).collect();
+        Ok(Response::new(GetPostsResponse {
+            posts: grpc_posts,
+            next_page_token: page.next_cursor.unwrap_or_default(),
+        }))
+    }
+
+    async fn get_feed(
+        &self,
+        request: Request<GetFeedRequest>,
+    ) -> Result<Response<GetPostsResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let page = self
+            .service
+            .get_feed(
+                user.id,
+                Some(req.page_token).filter(|t| !t.is_empty()),
+                u32::try_from(req.page_size).ok().filter(|&n| n > 0),
+            )
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(GetPostsResponse {
+            posts: page.posts.into_iter().map(domain_to_grpc).collect(),
+            next_page_token: page.next_cursor.unwrap_or_default(),
+        }))
     }
 
     async fn get_post(
         &self,
         request: Request<GetPostRequest>,
     ) -> Result<Response<GetPostResponse>, Status> {
-        let id = request.into_inner().id;
-        let post = self.service.get_post(id).await.map_err(map_error)?;
+        let viewer_id = viewer_id(&request);
+        let req = request.into_inner();
+        let post = self
+            .service
+            .get_post(req.id, viewer_id)
+            .await
+            .map_err(map_error)?;
         Ok(Response::new(GetPostResponse {
             post: Some(domain_to_grpc(post)),
         }))
     }
 
+    async fn get_post_by_slug(
+        &self,
+        request: Request<GetPostBySlugRequest>,
+    ) -> Result<Response<GetPostBySlugResponse>, Status> {
+        let viewer_id = viewer_id(&request);
+        let req = request.into_inner();
+        let (post, moved) = match self
+            .service
+            .get_post_by_slug(&req.slug, viewer_id)
+            .await
+            .map_err(map_error)?
+        {
+            SlugLookup::Current(post) => (post, false),
+            SlugLookup::Moved(post) => (post, true),
+        };
+        Ok(Response::new(GetPostBySlugResponse {
+            post: Some(domain_to_grpc(post)),
+            moved,
+        }))
+    }
+
     async fn update_post(
         &self,
         request: Request<UpdatePostRequest>,
     ) -> Result<Response<UpdatePostResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
         let req = request.into_inner();
-        let current_user = crate::presentation::auth::AuthenticatedUser {
-            id: req.author_id,
-            email: "stub@example.com".to_string(), // stub, не используется в update/delete логике кроме проверки id
-        };
         let post = self
             .service
-            .update_post(req.id, req.title, req.content, current_user)
+            .update_post(
+                req.id,
+                req.title,
+                req.content,
+                req.tags.map(|tags| tags.names),
+                expected_versions(req.expected_version),
+                user,
+            )
             .await
             .map_err(map_error)?;
         Ok(Response::new(UpdatePostResponse {
@@ -113,17 +336,32 @@ where
         }))
     }
 
+    async fn patch_post(
+        &self,
+        request: Request<PatchPostRequest>,
+    ) -> Result<Response<PatchPostResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let patch =
+            patch_from_mask(req.post.unwrap_or_default(), req.update_mask).map_err(map_error)?;
+        let post = self
+            .service
+            .patch_post(req.id, patch, expected_versions(req.expected_version), user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(PatchPostResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
     async fn delete_post(
         &self,
         request: Request<DeletePostRequest>,
     ) -> Result<Response<DeletePostResponse>, Status> {
-        let id = request.into_inner().id;
-        let current_user = crate::presentation::auth::AuthenticatedUser {
-            id: 0, // ← проблема! Нужен реальный user_id
-            email: "stub@example.com".to_string(),
-        };
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
         self.service
-            .delete_post(id, current_user)
+            .delete_post(req.id, expected_versions(req.expected_version), user)
             .await
             .map_err(|e| {
                 if matches!(e, PostError::Forbidden) {
@@ -134,4 +372,299 @@ where
             })?;
         Ok(Response::new(DeletePostResponse {}))
     }
+
+    async fn list_trash(
+        &self,
+        request: Request<ListTrashRequest>,
+    ) -> Result<Response<ListTrashResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let posts = self.service.list_trash(user).await.map_err(map_error)?;
+        Ok(Response::new(ListTrashResponse {
+            posts: posts
+                .into_iter()
+                .map(|trashed| GrpcTrashedPost {
+                    purge_at: trashed.purge_at.to_rfc3339(),
+                    post: Some(domain_to_grpc(trashed.post)),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn restore_post(
+        &self,
+        request: Request<RestorePostRequest>,
+    ) -> Result<Response<RestorePostResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let post = self
+            .service
+            .restore_post(req.id, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(RestorePostResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
+    async fn search_posts(
+        &self,
+        request: Request<SearchPostsRequest>,
+    ) -> Result<Response<SearchPostsResponse>, Status> {
+        let viewer_id = viewer_id(&request);
+        let req = request.into_inner();
+        let limit = u32::try_from(req.page_size).ok().filter(|&n| n > 0);
+        let page = self
+            .service
+            .search_posts(&req.query, viewer_id, Some(req.page_token.as_str()), limit)
+            .await
+            .map_err(map_error)?;
+        let hits = page
+            .hits
+            .into_iter()
+            .map(|hit| SearchHit {
+                post: Some(domain_to_grpc(hit.post)),
+                rank: hit.rank,
+                title_highlight: hit.title_highlight,
+                snippet: hit.snippet,
+            })
+            .collect();
+        Ok(Response::new(SearchPostsResponse {
+            hits,
+            next_page_token: page.next_cursor.unwrap_or_default(),
+        }))
+    }
+
+    async fn publish_post(
+        &self,
+        request: Request<PublishPostRequest>,
+    ) -> Result<Response<PublishPostResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let post = self
+            .service
+            .publish_post(req.id, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(PublishPostResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
+    async fn unpublish_post(
+        &self,
+        request: Request<UnpublishPostRequest>,
+    ) -> Result<Response<UnpublishPostResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let post = self
+            .service
+            .unpublish_post(req.id, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(UnpublishPostResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
+    async fn archive_post(
+        &self,
+        request: Request<ArchivePostRequest>,
+    ) -> Result<Response<ArchivePostResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let post = self
+            .service
+            .archive_post(req.id, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(ArchivePostResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
+    async fn schedule_post(
+        &self,
+        request: Request<SchedulePostRequest>,
+    ) -> Result<Response<SchedulePostResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let publish_at = Some(req.publish_at.as_str())
+            .filter(|s| !s.is_empty())
+            .map(parse_timestamp)
+            .transpose()
+            .map_err(|e| map_error(e.into()))?;
+        let post = self
+            .service
+            .schedule_post(req.id, publish_at, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(SchedulePostResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
+    async fn set_comment_mode(
+        &self,
+        request: Request<SetCommentModeRequest>,
+    ) -> Result<Response<SetCommentModeResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let mode = CommentMode::parse(&req.mode).map_err(|e| map_error(e.into()))?;
+        let post = self
+            .service
+            .set_comment_mode(req.id, mode, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(SetCommentModeResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
+    async fn add_reaction(
+        &self,
+        request: Request<ReactionRequest>,
+    ) -> Result<Response<ReactionResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let kind = ReactionKind::parse(&req.kind).map_err(|e| map_error(e.into()))?;
+        let post = self
+            .service
+            .react(req.post_id, kind, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(ReactionResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
+    async fn remove_reaction(
+        &self,
+        request: Request<ReactionRequest>,
+    ) -> Result<Response<ReactionResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let kind = ReactionKind::parse(&req.kind).map_err(|e| map_error(e.into()))?;
+        let post = self
+            .service
+            .unreact(req.post_id, kind, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(ReactionResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
+    async fn list_post_revisions(
+        &self,
+        request: Request<ListPostRevisionsRequest>,
+    ) -> Result<Response<ListPostRevisionsResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let revisions = self
+            .service
+            .list_revisions(req.post_id, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(ListPostRevisionsResponse {
+            revisions: revisions.into_iter().map(revision_to_grpc).collect(),
+        }))
+    }
+
+    async fn get_post_revision(
+        &self,
+        request: Request<GetPostRevisionRequest>,
+    ) -> Result<Response<GetPostRevisionResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let revision = self
+            .service
+            .get_revision(req.post_id, req.revision, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(GetPostRevisionResponse {
+            revision: Some(revision_to_grpc(revision)),
+        }))
+    }
+
+    async fn diff_post_revisions(
+        &self,
+        request: Request<DiffPostRevisionsRequest>,
+    ) -> Result<Response<DiffPostRevisionsResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let diff = self
+            .service
+            .diff_revisions(req.post_id, req.from_revision, req.to_revision, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(DiffPostRevisionsResponse {
+            title: diff_to_grpc(diff.title),
+            content: diff_to_grpc(diff.content),
+        }))
+    }
+
+    async fn restore_post_revision(
+        &self,
+        request: Request<RestorePostRevisionRequest>,
+    ) -> Result<Response<RestorePostRevisionResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let post = self
+            .service
+            .restore_revision(req.post_id, req.revision, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(RestorePostRevisionResponse {
+            post: Some(domain_to_grpc(post)),
+        }))
+    }
+
+    async fn list_collaborators(
+        &self,
+        request: Request<ListCollaboratorsRequest>,
+    ) -> Result<Response<ListCollaboratorsResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let collaborators = self
+            .service
+            .list_collaborators(req.post_id, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(ListCollaboratorsResponse {
+            collaborators: collaborators
+                .into_iter()
+                .map(collaborator_to_grpc)
+                .collect(),
+        }))
+    }
+
+    async fn set_collaborator(
+        &self,
+        request: Request<SetCollaboratorRequest>,
+    ) -> Result<Response<SetCollaboratorResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        let role = PostRole::parse(&req.role).map_err(|e| map_error(e.into()))?;
+        let collaborator = self
+            .service
+            .set_collaborator(req.post_id, req.user_id, role, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(SetCollaboratorResponse {
+            collaborator: Some(collaborator_to_grpc(collaborator)),
+        }))
+    }
+
+    async fn remove_collaborator(
+        &self,
+        request: Request<RemoveCollaboratorRequest>,
+    ) -> Result<Response<RemoveCollaboratorResponse>, Status> {
+        let user = current_user(&request).map_err(map_error)?;
+        let req = request.into_inner();
+        self.service
+            .remove_collaborator(req.post_id, req.user_id, user)
+            .await
+            .map_err(map_error)?;
+        Ok(Response::new(RemoveCollaboratorResponse {}))
+    }
 }
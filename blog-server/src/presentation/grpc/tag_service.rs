@@ -0,0 +1,76 @@
+use crate::application::blog_service::PostService;
+use crate::application::tag_service::TagService;
+use crate::data::post_repository::PostRepository;
+use crate::data::tag_repository::TagRepository;
+use crate::domain::post_query::PostListParams;
+use crate::presentation::grpc::auth::viewer_id;
+use crate::presentation::grpc::post_service::{domain_to_grpc, map_error};
+use crate::tag_service_server::TagService as GrpcTagService;
+use crate::{GetPostsResponse, GetTagPostsRequest, ListTagsRequest, ListTagsResponse, Tag};
+use std::sync::Arc;
+use tonic::{Request, Response, Status};
+
+// Обёртка над TagService для gRPC; посты по тегу отдаёт PostService
+pub struct TagGrpcService<R, T>
+where
+    R: PostRepository + 'static,
+    T: TagRepository + 'static,
+{
+    posts: Arc<PostService<R>>,
+    tags: Arc<TagService<T>>,
+}
+
+impl<R, T> TagGrpcService<R, T>
+where
+    R: PostRepository + 'static,
+    T: TagRepository + 'static,
+{
+    pub fn new(posts: Arc<PostService<R>>, tags: Arc<TagService<T>>) -> Self {
+        Self { posts, tags }
+    }
+}
+
+#[tonic::async_trait]
+impl<R, T> GrpcTagService for TagGrpcService<R, T>
+where
+    R: PostRepository + Send + Sync + 'static,
+    T: TagRepository + Send + Sync + 'static,
+{
+    async fn list_tags(
+        &self,
+        _request: Request<ListTagsRequest>,
+    ) -> Result<Response<ListTagsResponse>, Status> {
+        let tags = self.tags.list_tags().await.map_err(map_error)?;
+        Ok(Response::new(ListTagsResponse {
+            tags: tags
+                .into_iter()
+                .map(|tag| Tag {
+                    name: tag.name,
+                    post_count: tag.post_count,
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_tag_posts(
+        &self,
+        request: Request<GetTagPostsRequest>,
+    ) -> Result<Response<GetPostsResponse>, Status> {
+        let viewer_id = viewer_id(&request);
+        let req = request.into_inner();
+        let non_empty = |s: String| Some(s).filter(|s| !s.is_empty());
+        let params = PostListParams {
+            viewer_id,
+            tag: Some(req.name),
+            sort: non_empty(req.sort),
+            cursor: non_empty(req.page_token),
+            limit: u32::try_from(req.page_size).ok().filter(|&n| n > 0),
+            ..Default::default()
+        };
+        let page = self.posts.get_posts(params).await.map_err(map_error)?;
+        Ok(Response::new(GetPostsResponse {
+            posts: page.posts.into_iter().map(domain_to_grpc).collect(),
+            next_page_token: page.next_cursor.unwrap_or_default(),
+        }))
+    }
+}
@@ -0,0 +1,164 @@
+use crate::application::comment_service::CommentService;
+use crate::data::comment_repository::PostgresCommentRepository;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::domain::comment::CommentStatus;
+use crate::domain::error::PostError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::{CommentRequest, CommentUpdateRequest, ModerationRequest};
+use actix_web::{HttpResponse, Responder, Scope, delete, get, post, put, web};
+
+type Comments = CommentService<PostgresCommentRepository, PostgresPostRepository>;
+
+// Вложенный scope: полные пути вида /api/post/{post_id}/comments
+pub fn scope() -> Scope {
+    web::scope("/{post_id}/comments")
+        .service(create_comment)
+        .service(list_comments)
+        .service(list_pending_comments)
+        .service(update_comment)
+        .service(delete_comment)
+        .service(approve_comment)
+        .service(reject_comment)
+        .service(spam_comment)
+}
+
+#[post("")]
+async fn create_comment(
+    service: web::Data<Comments>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+    payload: web::Json<CommentRequest>,
+) -> Result<impl Responder, PostError> {
+    let payload = payload.into_inner();
+    let comment = service
+        .add_comment(path.into_inner(), payload.parent_id, payload.content, user)
+        .await?;
+    Ok(HttpResponse::Created().json(comment))
+}
+
+#[get("")]
+async fn list_comments(
+    service: web::Data<Comments>,
+    user: Option<AuthenticatedUser>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let comments = service
+        .list_comments(path.into_inner(), user.map(|user| user.id))
+        .await?;
+    Ok(HttpResponse::Ok().json(comments))
+}
+
+#[get("/pending")]
+async fn list_pending_comments(
+    service: web::Data<Comments>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let comments = service.list_pending(path.into_inner(), user).await?;
+    Ok(HttpResponse::Ok().json(comments))
+}
+
+#[put("/{comment_id}")]
+async fn update_comment(
+    service: web::Data<Comments>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i64)>,
+    payload: web::Json<CommentUpdateRequest>,
+) -> Result<impl Responder, PostError> {
+    let (post_id, comment_id) = path.into_inner();
+    let comment = service
+        .update_comment(post_id, comment_id, payload.into_inner().content, user)
+        .await?;
+    Ok(HttpResponse::Ok().json(comment))
+}
+
+#[delete("/{comment_id}")]
+async fn delete_comment(
+    service: web::Data<Comments>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i64)>,
+) -> Result<impl Responder, PostError> {
+    let (post_id, comment_id) = path.into_inner();
+    service.delete_comment(post_id, comment_id, user).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/{comment_id}/approve")]
+async fn approve_comment(
+    service: web::Data<Comments>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i64)>,
+    body: web::Bytes,
+) -> Result<impl Responder, PostError> {
+    moderate(service, user, path, body, CommentStatus::Approved).await
+}
+
+#[post("/{comment_id}/reject")]
+async fn reject_comment(
+    service: web::Data<Comments>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i64)>,
+    body: web::Bytes,
+) -> Result<impl Responder, PostError> {
+    moderate(service, user, path, body, CommentStatus::Rejected).await
+}
+
+#[post("/{comment_id}/spam")]
+async fn spam_comment(
+    service: web::Data<Comments>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i64)>,
+    body: web::Bytes,
+) -> Result<impl Responder, PostError> {
+    moderate(service, user, path, body, CommentStatus::Spam).await
+}
+
+// Тело с причиной необязательно, но если оно есть, то должно быть корректным JSON
+async fn moderate(
+    service: web::Data<Comments>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i64)>,
+    body: web::Bytes,
+    decision: CommentStatus,
+) -> Result<HttpResponse, PostError> {
+    let (post_id, comment_id) = path.into_inner();
+    let reason = parse_moderation(&body)?.reason;
+    let comment = service
+        .moderate_comment(post_id, comment_id, decision, reason, user)
+        .await?;
+    Ok(HttpResponse::Ok().json(comment))
+}
+
+fn parse_moderation(body: &[u8]) -> Result<ModerationRequest, PostError> {
+    if body.trim_ascii().is_empty() {
+        return Ok(ModerationRequest::default());
+    }
+    serde_json::from_slice(body).map_err(|e| PostError::Validation(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moderation_body_is_optional_but_must_be_valid() {
+        assert_eq!(parse_moderation(b"").unwrap().reason, None);
+        assert_eq!(parse_moderation(b" \n").unwrap().reason, None);
+        assert_eq!(parse_moderation(b"{}").unwrap().reason, None);
+        assert_eq!(
+            parse_moderation(br#"{"reason":"off-topic"}"#)
+                .unwrap()
+                .reason
+                .as_deref(),
+            Some("off-topic")
+        );
+        assert!(matches!(
+            parse_moderation(b"{reason"),
+            Err(PostError::Validation(_))
+        ));
+        assert!(matches!(
+            parse_moderation(br#"{"reason":1}"#),
+            Err(PostError::Validation(_))
+        ));
+    }
+}
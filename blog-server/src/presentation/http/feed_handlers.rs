@@ -0,0 +1,26 @@
+use crate::application::blog_service::PostService;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::domain::error::PostError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::PageQuery;
+use actix_web::{HttpResponse, Responder, Scope, get, web};
+
+pub fn scope() -> Scope {
+    web::scope("/feed")
+        .app_data(
+            web::QueryConfig::default()
+                .error_handler(|err, _| PostError::Validation(err.to_string()).into()),
+        )
+        .service(get_feed)
+}
+
+#[get("")]
+async fn get_feed(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    query: web::Query<PageQuery>,
+) -> Result<impl Responder, PostError> {
+    let query = query.into_inner();
+    let page = service.get_feed(user.id, query.cursor, query.limit).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
@@ -0,0 +1,126 @@
+use crate::application::auth_service::AuthService;
+use crate::application::blog_service::PostService;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::error::PostError;
+use crate::domain::post_query::PostListParams;
+use crate::domain::tag::normalize_tag;
+use crate::infrastructure::config::AppConfig;
+use crate::presentation::syndication::{FeedChannel, FeedFormat, render_feed};
+use actix_web::http::header::{CacheControl, CacheDirective};
+use actix_web::{HttpResponse, Responder, Scope, get, web};
+
+// Сколько последних постов попадает в ленту
+pub const FEED_SIZE: u32 = 20;
+const FEED_MAX_AGE_SECS: u32 = 300;
+
+pub fn scope() -> Scope {
+    web::scope("/feeds")
+        .service(site_feed)
+        .service(author_feed)
+        .service(tag_feed)
+}
+
+#[get("/{file}")]
+async fn site_feed(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    config: web::Data<AppConfig>,
+    path: web::Path<String>,
+) -> Result<impl Responder, PostError> {
+    let file = path.into_inner();
+    let format = parse_format(&file)?;
+    let channel = FeedChannel {
+        title: config.site_title.clone(),
+        description: format!("Latest posts on {}", config.site_title),
+        site_url: config.site_url.clone(),
+        post_url_template: config.post_url.clone(),
+        feed_url: format!("{}/feeds/{}", config.site_url, file),
+        author: config.site_title.clone(),
+        excerpts: config.feed_excerpts,
+    };
+    feed_response(&service, format, channel, PostListParams::default()).await
+}
+
+#[get("/authors/{author_id}/{file}")]
+async fn author_feed(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    auth: web::Data<AuthService<PostgresUserRepository>>,
+    config: web::Data<AppConfig>,
+    path: web::Path<(i64, String)>,
+) -> Result<impl Responder, PostError> {
+    let (author_id, file) = path.into_inner();
+    let format = parse_format(&file)?;
+    let author = auth
+        .get_user(author_id)
+        .await
+        .map_err(|_| PostError::PostNotFound(format!("author {}", author_id)))?;
+    let channel = FeedChannel {
+        title: format!("{} — {}", author.username, config.site_title),
+        description: format!("Posts by {}", author.username),
+        site_url: config.site_url.clone(),
+        post_url_template: config.post_url.clone(),
+        feed_url: format!("{}/feeds/authors/{}/{}", config.site_url, author_id, file),
+        author: author.username,
+        excerpts: config.feed_excerpts,
+    };
+    let params = PostListParams {
+        author_id: Some(author_id),
+        ..Default::default()
+    };
+    feed_response(&service, format, channel, params).await
+}
+
+#[get("/tags/{tag}/{file}")]
+async fn tag_feed(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    config: web::Data<AppConfig>,
+    path: web::Path<(String, String)>,
+) -> Result<impl Responder, PostError> {
+    let (tag, file) = path.into_inner();
+    let format = parse_format(&file)?;
+    let tag = normalize_tag(&tag)?;
+    let channel = FeedChannel {
+        title: format!("#{} — {}", tag, config.site_title),
+        description: format!("Posts tagged {}", tag),
+        site_url: config.site_url.clone(),
+        post_url_template: config.post_url.clone(),
+        feed_url: format!("{}/feeds/tags/{}/{}", config.site_url, tag, file),
+        author: config.site_title.clone(),
+        excerpts: config.feed_excerpts,
+    };
+    let params = PostListParams {
+        tag: Some(tag),
+        ..Default::default()
+    };
+    feed_response(&service, format, channel, params).await
+}
+
+fn parse_format(file: &str) -> Result<FeedFormat, PostError> {
+    FeedFormat::from_file_name(file)
+        .ok_or_else(|| PostError::PostNotFound(format!("feed {}", file)))
+}
+
+// Ленты анонимные: в них попадают только опубликованные посты
+async fn feed_response(
+    service: &PostService<PostgresPostRepository>,
+    format: FeedFormat,
+    channel: FeedChannel,
+    params: PostListParams,
+) -> Result<HttpResponse, PostError> {
+    let params = PostListParams {
+        viewer_id: None,
+        limit: Some(FEED_SIZE),
+        ..params
+    };
+    let page = service.get_posts(params).await?;
+    let body = render_feed(format, &channel, &page.posts);
+
+    // ETag и 304 добавляет HttpCacheMiddleware
+    Ok(HttpResponse::Ok()
+        .insert_header(CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(FEED_MAX_AGE_SECS),
+        ]))
+        .content_type(format.content_type())
+        .body(body))
+}
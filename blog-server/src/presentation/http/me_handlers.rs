@@ -0,0 +1,28 @@
+use crate::application::blog_service::PostService;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::domain::error::PostError;
+use crate::presentation::auth::AuthenticatedUser;
+use actix_web::{HttpResponse, Responder, Scope, get, post, web};
+
+pub fn scope() -> Scope {
+    web::scope("/me").service(list_trash).service(restore_post)
+}
+
+#[get("/trash")]
+async fn list_trash(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+) -> Result<impl Responder, PostError> {
+    let posts = service.list_trash(user).await?;
+    Ok(HttpResponse::Ok().json(posts))
+}
+
+#[post("/trash/{id}/restore")]
+async fn restore_post(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let post = service.restore_post(path.into_inner(), user).await?;
+    Ok(HttpResponse::Ok().json(post))
+}
@@ -0,0 +1,100 @@
+use crate::application::media_service::MediaService;
+use crate::data::media_repository::PostgresMediaRepository;
+use crate::domain::error::PostError;
+use crate::presentation::auth::AuthenticatedUser;
+use actix_multipart::Multipart;
+use actix_web::http::header::{self, CacheControl, CacheDirective, EntityTag};
+use actix_web::{HttpResponse, Responder, Scope, get, post, web};
+use bytes::BytesMut;
+use futures_util::TryStreamExt;
+
+// Имя поля multipart-формы с файлом
+const FILE_FIELD: &str = "file";
+// Ключ файла не меняется при изменении содержимого, поэтому кэшировать можно бессрочно
+const MEDIA_MAX_AGE_SECS: u32 = 365 * 24 * 60 * 60;
+
+pub fn scope() -> Scope {
+    web::scope("/media")
+        .service(upload_media)
+        .service(list_media)
+        .service(get_own_media)
+}
+
+// Раздача файлов по адресам из текста постов, без токена
+pub fn public_scope() -> Scope {
+    web::scope("/media").service(get_media)
+}
+
+#[post("")]
+async fn upload_media(
+    service: web::Data<MediaService<PostgresMediaRepository>>,
+    user: AuthenticatedUser,
+    mut payload: Multipart,
+) -> Result<impl Responder, PostError> {
+    let limit = service.max_file_bytes() as usize;
+    while let Some(mut field) = payload.try_next().await.map_err(multipart_error)? {
+        if field.name() != Some(FILE_FIELD) {
+            continue;
+        }
+        // Читаем не больше лимита, чтобы не держать в памяти заведомо слишком большой файл
+        let mut data = BytesMut::new();
+        while let Some(chunk) = field.try_next().await.map_err(multipart_error)? {
+            if data.len() + chunk.len() > limit {
+                return Err(PostError::PayloadTooLarge(format!(
+                    "file exceeds {} bytes",
+                    limit
+                )));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        let media = service.upload(user.id, data.freeze()).await?;
+        return Ok(HttpResponse::Created()
+            .insert_header((header::LOCATION, media.url.clone()))
+            .json(media));
+    }
+    Err(PostError::Validation(format!(
+        "multipart field \"{}\" is required",
+        FILE_FIELD
+    )))
+}
+
+#[get("")]
+async fn list_media(
+    service: web::Data<MediaService<PostgresMediaRepository>>,
+    user: AuthenticatedUser,
+) -> Result<impl Responder, PostError> {
+    let media = service.list(user.id).await?;
+    Ok(HttpResponse::Ok().json(media))
+}
+
+// Статус обработки и копии одного файла; клиент опрашивает его после загрузки
+#[get("/{id}")]
+async fn get_own_media(
+    service: web::Data<MediaService<PostgresMediaRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let media = service.get(user.id, path.into_inner()).await?;
+    Ok(HttpResponse::Ok().json(media))
+}
+
+#[get("/{key}")]
+async fn get_media(
+    service: web::Data<MediaService<PostgresMediaRepository>>,
+    path: web::Path<String>,
+) -> Result<impl Responder, PostError> {
+    let (object, data) = service.open(&path).await?;
+    Ok(HttpResponse::Ok()
+        .content_type(object.mime_type)
+        .insert_header(header::ETag(EntityTag::new_strong(object.checksum)))
+        .insert_header(CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(MEDIA_MAX_AGE_SECS),
+            CacheDirective::Extension("immutable".into(), None),
+        ]))
+        .body(data))
+}
+
+fn multipart_error(e: actix_multipart::MultipartError) -> PostError {
+    PostError::Validation(format!("invalid multipart body: {}", e))
+}
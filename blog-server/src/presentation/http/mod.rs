@@ -1,3 +1,11 @@
 pub mod auth_handlers;
+pub mod comments_handlers;
+pub mod feed_handlers;
+pub mod feeds_handlers;
 pub mod help_handlers;
+pub mod me_handlers;
+pub mod media_handlers;
 pub mod posts_hendlers;
+pub mod series_handlers;
+pub mod tags_handlers;
+pub mod users_handlers;
\ No newline at end of file
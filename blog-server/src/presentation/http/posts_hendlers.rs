@@ -1,19 +1,69 @@
+use crate::application::analytics_service::AnalyticsService;
 use crate::application::blog_service::PostService;
+use crate::data::analytics_repository::PostgresAnalyticsRepository;
 use crate::data::post_repository::PostgresPostRepository;
 use crate::domain::error::PostError;
+use crate::domain::post::{Post, PostPatch, PostStatus, SlugLookup};
+use crate::domain::post_query::PostListParams;
+use crate::domain::reaction::ReactionKind;
+use crate::infrastructure::config::AppConfig;
 use crate::presentation::auth::AuthenticatedUser;
-use crate::presentation::dto::PostRequest;
-use actix_web::{HttpResponse, Responder, Scope, delete, get, post, put, web};
+use crate::presentation::dto::{
+    CollaboratorRequest, CommentModeRequest, PostMergePatch, PostRequest, PostsQuery,
+    RevisionDiffQuery, ScheduleRequest, SearchQuery, StatsQuery,
+};
+use crate::presentation::http::comments_handlers;
+use crate::presentation::middleware::body_etag;
+use actix_web::http::header::{self, ContentType, EntityTag, IfMatch};
+use actix_web::{
+    HttpMessage, HttpRequest, HttpResponse, Responder, Scope, delete, get, patch, post, put, web,
+};
+use std::net::IpAddr;
 
-use tracing;
+const MERGE_PATCH_CONTENT_TYPE: &str = "application/merge-patch+json";
+
+type Analytics = AnalyticsService<PostgresAnalyticsRepository, PostgresPostRepository>;
+
+// Маршруты, которые читаются без токена; остальные требуют авторизации
+pub const ANONYMOUS_READS: &[&str] = &[
+    "/api/post",
+    "/api/post/search",
+    "/api/post/by-slug/{slug}",
+    "/api/post/{id}",
+    "/api/post/{post_id}/comments",
+];
 
 pub fn scope() -> Scope {
     web::scope("/post")
+        .app_data(
+            web::QueryConfig::default()
+                .error_handler(|err, _| PostError::Validation(err.to_string()).into()),
+        )
         .service(create_post)
         .service(get_posts)
+        .service(search_posts)
+        .service(get_post_by_slug)
         .service(get_post)
         .service(update_post)
+        .service(patch_post)
         .service(delete_post)
+        .service(publish_post)
+        .service(unpublish_post)
+        .service(archive_post)
+        .service(set_comment_mode)
+        .service(add_reaction)
+        .service(remove_reaction)
+        .service(schedule_post)
+        .service(unschedule_post)
+        .service(list_revisions)
+        .service(diff_revisions)
+        .service(get_revision)
+        .service(restore_revision)
+        .service(list_collaborators)
+        .service(set_collaborator)
+        .service(remove_collaborator)
+        .service(post_stats)
+        .service(comments_handlers::scope())
 }
 
 #[post("")]
@@ -22,14 +72,21 @@ async fn create_post(
     user: AuthenticatedUser,
     payload: web::Json<PostRequest>,
 ) -> Result<impl Responder, PostError> {
-    println!("{:?}", user);
     let new_post = service
-        .create_post(payload.title.clone(), payload.content.clone(), user.id)
+        .create_post(
+            payload.title.clone(),
+            payload.content.clone(),
+            payload.status.unwrap_or(PostStatus::Published),
+            payload.tags.clone().unwrap_or_default(),
+            user.id,
+        )
         .await;
     match new_post {
         Ok(post) => Ok(HttpResponse::Created().json(PostRequest {
             title: post.title,
             content: post.content,
+            status: Some(post.status),
+            tags: Some(post.tags),
         })),
         Err(e) => Err(e),
     }
@@ -37,56 +94,482 @@ async fn create_post(
 
 #[get("")]
 async fn get_posts(
+    req: HttpRequest,
     service: web::Data<PostService<PostgresPostRepository>>,
-    user: AuthenticatedUser,
+    user: Option<AuthenticatedUser>,
+    query: web::Query<PostsQuery>,
 ) -> Result<impl Responder, PostError> {
-    let posts = service.get_posts().await?;
-    tracing::info!(email = %user.email, "user logged in");
-    Ok(HttpResponse::Ok().json(posts))
+    let params = PostListParams {
+        viewer_id: user.map(|user| user.id),
+        ..query.into_inner().into()
+    };
+    let page = service.get_posts(params).await?;
+    // Тело остаётся прежним массивом постов, чтобы не ломать существующих клиентов;
+    // курсор следующей страницы передаётся в заголовке Link (RFC 8288)
+    let mut response = HttpResponse::Ok();
+    if let Some(cursor) = &page.next_cursor {
+        response.insert_header((header::LINK, next_page_link(&req, cursor)));
+    }
+    Ok(response.json(page.posts))
+}
+
+#[get("/search")]
+async fn search_posts(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: Option<AuthenticatedUser>,
+    query: web::Query<SearchQuery>,
+) -> Result<impl Responder, PostError> {
+    let query = query.into_inner();
+    let page = service
+        .search_posts(
+            &query.q,
+            user.map(|user| user.id),
+            query.cursor.as_deref(),
+            query.limit,
+        )
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+// Старый slug отвечает 301 на актуальный адрес поста
+#[get("/by-slug/{slug}", name = "post_by_slug")]
+async fn get_post_by_slug(
+    req: HttpRequest,
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: Option<AuthenticatedUser>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, PostError> {
+    match service
+        .get_post_by_slug(&path, user.map(|user| user.id))
+        .await?
+    {
+        SlugLookup::Current(post) => post_response(post),
+        SlugLookup::Moved(post) => {
+            let location = req
+                .url_for("post_by_slug", [&post.slug])
+                .map_err(|e| PostError::Internal(e.to_string()))?;
+            Ok(HttpResponse::MovedPermanently()
+                .insert_header((header::LOCATION, location.path()))
+                .finish())
+        }
+    }
 }
 
 #[get("/{id}")]
 async fn get_post(
+    req: HttpRequest,
     service: web::Data<PostService<PostgresPostRepository>>,
-    user: AuthenticatedUser,
-    path: web::Path<String>,
+    analytics: web::Data<Analytics>,
+    config: web::Data<AppConfig>,
+    user: Option<AuthenticatedUser>,
+    path: web::Path<i64>,
 ) -> Result<impl Responder, PostError> {
-    let id = path.into_inner();
-    let post = service.get_post(id.parse().unwrap()).await?;
-    tracing::info!(email = %user.email, "user logged in");
-    Ok(HttpResponse::Ok().json(post))
+    let viewer_id = user.map(|user| user.id);
+    let post = service.get_post(path.into_inner(), viewer_id).await?;
+    let user_agent = req
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|value| value.to_str().ok());
+    analytics.record_view(
+        &post,
+        viewer_id,
+        client_ip(&req, &config.trusted_proxies),
+        user_agent,
+    );
+    post_response(post)
+}
+
+// Адрес клиента берётся из X-Forwarded-For, только если соединение пришло от доверенного прокси.
+// Прокси дописывают адреса справа, поэтому клиент — первый с конца недоверенный адрес
+fn client_ip(req: &HttpRequest, trusted_proxies: &[IpAddr]) -> Option<IpAddr> {
+    let peer = req.peer_addr()?.ip();
+    if !trusted_proxies.contains(&peer) {
+        return Some(peer);
+    }
+    let forwarded: Vec<IpAddr> = req
+        .headers()
+        .get_all(header::X_FORWARDED_FOR)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect();
+    forwarded
+        .into_iter()
+        .rev()
+        .find(|ip| !trusted_proxies.contains(ip))
+        .or(Some(peer))
 }
 
 #[put("/{id}")]
 async fn update_post(
+    req: HttpRequest,
     service: web::Data<PostService<PostgresPostRepository>>,
+    config: web::Data<AppConfig>,
     user: AuthenticatedUser,
-    path: web::Path<String>,
+    path: web::Path<i64>,
     payload: web::Json<PostRequest>,
 ) -> Result<impl Responder, PostError> {
-    let id = path.into_inner();
+    let expected_versions = expected_versions(&req, config.require_if_match)?;
     let post = service
         .update_post(
-            id.parse().unwrap(),
+            path.into_inner(),
             payload.title.clone(),
             payload.content.clone(),
+            payload.tags.clone(),
+            expected_versions,
+            user,
+        )
+        .await?;
+    post_response(post)
+}
+
+// Частичное обновление: меняются только поля, переданные в теле
+#[patch("/{id}")]
+async fn patch_post(
+    req: HttpRequest,
+    service: web::Data<PostService<PostgresPostRepository>>,
+    config: web::Data<AppConfig>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+    body: web::Bytes,
+) -> Result<impl Responder, PostError> {
+    if req.content_type() != MERGE_PATCH_CONTENT_TYPE {
+        return Err(PostError::UnsupportedMediaType(format!(
+            "expected {}",
+            MERGE_PATCH_CONTENT_TYPE
+        )));
+    }
+    let patch: PostMergePatch =
+        serde_json::from_slice(&body).map_err(|e| PostError::Validation(e.to_string()))?;
+    let expected_versions = expected_versions(&req, config.require_if_match)?;
+    let post = service
+        .patch_post(
+            path.into_inner(),
+            PostPatch::try_from(patch)?,
+            expected_versions,
             user,
         )
         .await?;
-    Ok(HttpResponse::Ok().json(post))
+    post_response(post)
 }
 
 #[delete("/{id}")]
 async fn delete_post(
+    req: HttpRequest,
     service: web::Data<PostService<PostgresPostRepository>>,
+    config: web::Data<AppConfig>,
     user: AuthenticatedUser,
-    path: web::Path<String>,
+    path: web::Path<i64>,
 ) -> Result<impl Responder, PostError> {
-    let id = path.into_inner();
-    let post = service.get_post(id.parse().unwrap()).await?;
-    if post.author_id != user.id {
-        return Err(PostError::Unauthorized);
-    }
-    service.delete_post(id.parse().unwrap(), user).await?;
+    let expected_versions = expected_versions(&req, config.require_if_match)?;
+    service
+        .delete_post(path.into_inner(), expected_versions, user)
+        .await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[post("/{id}/publish")]
+async fn publish_post(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let post = service.publish_post(path.into_inner(), user).await?;
+    post_response(post)
+}
+
+#[post("/{id}/unpublish")]
+async fn unpublish_post(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let post = service.unpublish_post(path.into_inner(), user).await?;
+    post_response(post)
+}
+
+#[post("/{id}/archive")]
+async fn archive_post(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let post = service.archive_post(path.into_inner(), user).await?;
+    post_response(post)
+}
+
+#[put("/{id}/comment-mode")]
+async fn set_comment_mode(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+    payload: web::Json<CommentModeRequest>,
+) -> Result<impl Responder, PostError> {
+    let post = service
+        .set_comment_mode(path.into_inner(), payload.mode, user)
+        .await?;
+    post_response(post)
+}
+
+#[put("/{id}/reactions/{kind}")]
+async fn add_reaction(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, String)>,
+) -> Result<impl Responder, PostError> {
+    let (id, kind) = path.into_inner();
+    let post = service.react(id, ReactionKind::parse(&kind)?, user).await?;
+    post_response(post)
+}
+
+#[delete("/{id}/reactions/{kind}")]
+async fn remove_reaction(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, String)>,
+) -> Result<impl Responder, PostError> {
+    let (id, kind) = path.into_inner();
+    let post = service
+        .unreact(id, ReactionKind::parse(&kind)?, user)
+        .await?;
+    post_response(post)
+}
+
+#[put("/{id}/schedule")]
+async fn schedule_post(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+    payload: web::Json<ScheduleRequest>,
+) -> Result<impl Responder, PostError> {
+    let post = service
+        .schedule_post(path.into_inner(), Some(payload.publish_at), user)
+        .await?;
+    post_response(post)
+}
+
+#[delete("/{id}/schedule")]
+async fn unschedule_post(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let post = service.schedule_post(path.into_inner(), None, user).await?;
+    post_response(post)
+}
+
+#[get("/{id}/revisions")]
+async fn list_revisions(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let revisions = service.list_revisions(path.into_inner(), user).await?;
+    Ok(HttpResponse::Ok().json(revisions))
+}
+
+#[get("/{id}/revisions/diff")]
+async fn diff_revisions(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+    query: web::Query<RevisionDiffQuery>,
+) -> Result<impl Responder, PostError> {
+    let diff = service
+        .diff_revisions(path.into_inner(), query.from, query.to, user)
+        .await?;
+    Ok(HttpResponse::Ok().json(diff))
+}
+
+#[get("/{id}/revisions/{revision}")]
+async fn get_revision(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i32)>,
+) -> Result<impl Responder, PostError> {
+    let (id, revision) = path.into_inner();
+    let revision = service.get_revision(id, revision, user).await?;
+    Ok(HttpResponse::Ok().json(revision))
+}
+
+#[post("/{id}/revisions/{revision}/restore")]
+async fn restore_revision(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i32)>,
+) -> Result<impl Responder, PostError> {
+    let (id, revision) = path.into_inner();
+    let post = service.restore_revision(id, revision, user).await?;
+    post_response(post)
+}
+
+#[get("/{id}/collaborators")]
+async fn list_collaborators(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let collaborators = service.list_collaborators(path.into_inner(), user).await?;
+    Ok(HttpResponse::Ok().json(collaborators))
+}
+
+#[put("/{id}/collaborators/{user_id}")]
+async fn set_collaborator(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i64)>,
+    payload: web::Json<CollaboratorRequest>,
+) -> Result<impl Responder, PostError> {
+    let (id, user_id) = path.into_inner();
+    let collaborator = service
+        .set_collaborator(id, user_id, payload.role, user)
+        .await?;
+    Ok(HttpResponse::Ok().json(collaborator))
+}
+
+#[delete("/{id}/collaborators/{user_id}")]
+async fn remove_collaborator(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<(i64, i64)>,
+) -> Result<impl Responder, PostError> {
+    let (id, user_id) = path.into_inner();
+    service.remove_collaborator(id, user_id, user).await?;
     Ok(HttpResponse::NoContent().finish())
 }
+
+// Статистика просмотров доступна только владельцам поста
+#[get("/{id}/stats")]
+async fn post_stats(
+    analytics: web::Data<Analytics>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+    query: web::Query<StatsQuery>,
+) -> Result<impl Responder, PostError> {
+    let stats = analytics
+        .post_stats(path.into_inner(), query.from, query.to, user)
+        .await?;
+    Ok(HttpResponse::Ok().json(stats))
+}
+
+// Ссылка на следующую страницу с теми же параметрами, что и у текущего запроса
+fn next_page_link(req: &HttpRequest, cursor: &str) -> String {
+    let cursor = format!("cursor={}", cursor);
+    let mut query: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !pair.starts_with("cursor="))
+        .collect();
+    query.push(&cursor);
+    format!("<{}?{}>; rel=\"next\"", req.path(), query.join("&"))
+}
+
+// ETag поста — "<версия>-<хэш тела>": версия нужна для If-Match, а хэш меняется вместе
+// с реакциями и отметками читателя, которые версию не трогают
+fn post_response(post: Post) -> Result<HttpResponse, PostError> {
+    let body = serde_json::to_vec(&post).map_err(|e| PostError::Internal(e.to_string()))?;
+    let etag = format!("{}-{}", post.version, body_etag(&body).tag());
+    Ok(HttpResponse::Ok()
+        .insert_header(header::ETag(EntityTag::new_strong(etag)))
+        .content_type(ContentType::json())
+        .body(body))
+}
+
+// Допустимые версии из If-Match: подходит любой из перечисленных тегов, "*" — любая версия.
+// Без заголовка проверка пропускается, а в строгом режиме запрос отклоняется с 428
+fn expected_versions(req: &HttpRequest, required: bool) -> Result<Option<Vec<i32>>, PostError> {
+    match req.get_header::<IfMatch>() {
+        None if required => Err(PostError::PreconditionRequired(
+            "If-Match header is required".into(),
+        )),
+        None | Some(IfMatch::Any) => Ok(None),
+        Some(IfMatch::Items(tags)) => {
+            // Слабые и чужие теги ни с одной версией поста не совпадут
+            let versions: Vec<i32> = tags
+                .iter()
+                .filter(|tag| !tag.weak)
+                .filter_map(|tag| tag.tag().split('-').next()?.parse().ok())
+                .collect();
+            if versions.is_empty() {
+                return Err(PostError::PreconditionFailed(
+                    "If-Match does not match any version".into(),
+                ));
+            }
+            Ok(Some(versions))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    fn request(peer: &str, forwarded: Option<&str>) -> HttpRequest {
+        let mut req = TestRequest::default().peer_addr(peer.parse().unwrap());
+        if let Some(forwarded) = forwarded {
+            req = req.insert_header((header::X_FORWARDED_FOR, forwarded));
+        }
+        req.to_http_request()
+    }
+
+    #[test]
+    fn trusts_forwarded_for_only_from_proxies() {
+        let proxy: IpAddr = "10.0.0.1".parse().unwrap();
+        let ip = |s: &str| Some(s.parse::<IpAddr>().unwrap());
+
+        // Клиент напрямую не может подменить свой адрес заголовком
+        let direct = request("203.0.113.7:5000", Some("198.51.100.1"));
+        assert_eq!(client_ip(&direct, &[proxy]), ip("203.0.113.7"));
+        assert_eq!(client_ip(&direct, &[]), ip("203.0.113.7"));
+
+        // За прокси берётся последний адрес, дописанный не доверенным прокси
+        let proxied = request("10.0.0.1:5000", Some("198.51.100.1, 203.0.113.7, 10.0.0.1"));
+        assert_eq!(client_ip(&proxied, &[proxy]), ip("203.0.113.7"));
+
+        let no_header = request("10.0.0.1:5000", None);
+        assert_eq!(client_ip(&no_header, &[proxy]), ip("10.0.0.1"));
+    }
+
+    #[test]
+    fn if_match_accepts_any_listed_version() {
+        let versions = |if_match: Option<&str>, required: bool| {
+            let mut req = TestRequest::default();
+            if let Some(if_match) = if_match {
+                req = req.insert_header((header::IF_MATCH, if_match));
+            }
+            expected_versions(&req.to_http_request(), required)
+        };
+
+        assert_eq!(
+            versions(Some(r#""3-abc", W/"4-def", "5-012""#), false).unwrap(),
+            Some(vec![3, 5])
+        );
+        assert_eq!(versions(Some("*"), true).unwrap(), None);
+        assert_eq!(versions(None, false).unwrap(), None);
+        assert!(matches!(
+            versions(None, true),
+            Err(PostError::PreconditionRequired(_))
+        ));
+        assert!(matches!(
+            versions(Some(r#"W/"3-abc", "other""#), false),
+            Err(PostError::PreconditionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn next_page_link_keeps_filters() {
+        let req = TestRequest::get()
+            .uri("/api/post?tag=rust&cursor=old&limit=2")
+            .to_http_request();
+        assert_eq!(
+            next_page_link(&req, "next"),
+            r#"</api/post?tag=rust&limit=2&cursor=next>; rel="next""#
+        );
+        let req = TestRequest::get().uri("/api/post").to_http_request();
+        assert_eq!(
+            next_page_link(&req, "next"),
+            r#"</api/post?cursor=next>; rel="next""#
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use crate::application::series_service::SeriesService;
+use crate::data::series_repository::PostgresSeriesRepository;
+use crate::domain::error::PostError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::{SeriesPostsRequest, SeriesRequest};
+use actix_web::{HttpResponse, Responder, Scope, delete, get, post, put, web};
+
+// Маршруты, которые читаются без токена
+pub const ANONYMOUS_READS: &[&str] = &["/api/series/{id}"];
+
+pub fn scope() -> Scope {
+    web::scope("/series")
+        .service(create_series)
+        .service(get_series)
+        .service(update_series)
+        .service(delete_series)
+        .service(set_series_posts)
+}
+
+#[post("")]
+async fn create_series(
+    service: web::Data<SeriesService<PostgresSeriesRepository>>,
+    user: AuthenticatedUser,
+    payload: web::Json<SeriesRequest>,
+) -> Result<impl Responder, PostError> {
+    let payload = payload.into_inner();
+    let series = service
+        .create_series(payload.title, payload.description, user)
+        .await?;
+    Ok(HttpResponse::Created().json(series))
+}
+
+// Оглавление серии: части по порядку
+#[get("/{id}")]
+async fn get_series(
+    service: web::Data<SeriesService<PostgresSeriesRepository>>,
+    user: Option<AuthenticatedUser>,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    let series = service
+        .get_series(path.into_inner(), user.map(|user| user.id))
+        .await?;
+    Ok(HttpResponse::Ok().json(series))
+}
+
+#[put("/{id}")]
+async fn update_series(
+    service: web::Data<SeriesService<PostgresSeriesRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+    payload: web::Json<SeriesRequest>,
+) -> Result<impl Responder, PostError> {
+    let payload = payload.into_inner();
+    let series = service
+        .update_series(path.into_inner(), payload.title, payload.description, user)
+        .await?;
+    Ok(HttpResponse::Ok().json(series))
+}
+
+#[delete("/{id}")]
+async fn delete_series(
+    service: web::Data<SeriesService<PostgresSeriesRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, PostError> {
+    service.delete_series(path.into_inner(), user).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+// Полностью заменяет состав серии; пустой список убирает из неё все посты
+#[put("/{id}/posts")]
+async fn set_series_posts(
+    service: web::Data<SeriesService<PostgresSeriesRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+    payload: web::Json<SeriesPostsRequest>,
+) -> Result<impl Responder, PostError> {
+    let series = service
+        .set_series_posts(path.into_inner(), payload.into_inner().post_ids, user)
+        .await?;
+    Ok(HttpResponse::Ok().json(series))
+}
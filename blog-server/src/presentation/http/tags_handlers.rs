@@ -0,0 +1,46 @@
+use crate::application::blog_service::PostService;
+use crate::application::tag_service::TagService;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::data::tag_repository::PostgresTagRepository;
+use crate::domain::error::PostError;
+use crate::domain::post_query::PostListParams;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::PostsQuery;
+use actix_web::{HttpResponse, Responder, Scope, get, web};
+
+// Маршруты, которые читаются без токена
+pub const ANONYMOUS_READS: &[&str] = &["/api/tags", "/api/tags/{name}/posts"];
+
+pub fn scope() -> Scope {
+    web::scope("/tags")
+        .app_data(
+            web::QueryConfig::default()
+                .error_handler(|err, _| PostError::Validation(err.to_string()).into()),
+        )
+        .service(list_tags)
+        .service(get_tag_posts)
+}
+
+#[get("")]
+async fn list_tags(
+    service: web::Data<TagService<PostgresTagRepository>>,
+) -> Result<impl Responder, PostError> {
+    let tags = service.list_tags().await?;
+    Ok(HttpResponse::Ok().json(tags))
+}
+
+#[get("/{name}/posts")]
+async fn get_tag_posts(
+    service: web::Data<PostService<PostgresPostRepository>>,
+    user: Option<AuthenticatedUser>,
+    path: web::Path<String>,
+    query: web::Query<PostsQuery>,
+) -> Result<impl Responder, PostError> {
+    let params = PostListParams {
+        viewer_id: user.map(|user| user.id),
+        tag: Some(path.into_inner()),
+        ..query.into_inner().into()
+    };
+    let page = service.get_posts(params).await?;
+    Ok(HttpResponse::Ok().json(page))
+}
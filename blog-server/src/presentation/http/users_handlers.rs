@@ -0,0 +1,64 @@
+use crate::application::follow_service::FollowService;
+use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::error::AuthError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::PageQuery;
+use actix_web::{HttpResponse, Responder, Scope, delete, get, put, web};
+
+pub fn scope() -> Scope {
+    web::scope("/users")
+        .app_data(
+            web::QueryConfig::default()
+                .error_handler(|err, _| AuthError::Validation(err.to_string()).into()),
+        )
+        .service(follow_user)
+        .service(unfollow_user)
+        .service(get_followers)
+        .service(get_following)
+}
+
+#[put("/{id}/follow")]
+async fn follow_user(
+    service: web::Data<FollowService<PostgresUserRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AuthError> {
+    service.follow(path.into_inner(), user).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[delete("/{id}/follow")]
+async fn unfollow_user(
+    service: web::Data<FollowService<PostgresUserRepository>>,
+    user: AuthenticatedUser,
+    path: web::Path<i64>,
+) -> Result<impl Responder, AuthError> {
+    service.unfollow(path.into_inner(), user).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[get("/{id}/followers")]
+async fn get_followers(
+    service: web::Data<FollowService<PostgresUserRepository>>,
+    path: web::Path<i64>,
+    query: web::Query<PageQuery>,
+) -> Result<impl Responder, AuthError> {
+    let query = query.into_inner();
+    let page = service
+        .followers(path.into_inner(), query.cursor.as_deref(), query.limit)
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
+
+#[get("/{id}/following")]
+async fn get_following(
+    service: web::Data<FollowService<PostgresUserRepository>>,
+    path: web::Path<i64>,
+    query: web::Query<PageQuery>,
+) -> Result<impl Responder, AuthError> {
+    let query = query.into_inner();
+    let page = service
+        .following(path.into_inner(), query.cursor.as_deref(), query.limit)
+        .await?;
+    Ok(HttpResponse::Ok().json(page))
+}
@@ -5,15 +5,21 @@ use std::task::{Context, Poll};
 use std::time::Instant;
 
 use actix_service::{Service, Transform};
+use actix_web::body::{self, BoxBody, MessageBody};
 use actix_web::dev::{ServiceRequest, ServiceResponse};
-use actix_web::http::header::{HeaderName, HeaderValue};
-use actix_web::{web, Error, HttpMessage};
+use actix_web::http::header::{
+    self, EntityTag, HeaderName, HeaderValue, IfNoneMatch,
+};
+use actix_web::http::{Method, StatusCode};
+use actix_web::{web, Error, HttpMessage, HttpRequest};
 use futures_util::future::LocalBoxFuture;
+use sha2::{Digest, Sha256};
 use tracing::info;
 use uuid::Uuid;
 
 use crate::application::auth_service::AuthService;
 use crate::data::user_repository::PostgresUserRepository;
+use crate::infrastructure::config::AppConfig;
 use crate::infrastructure::jwt::JwtKeys;
 use crate::presentation::auth::extract_user_from_token;
 
@@ -161,13 +167,151 @@ where
     }
 }
 
+// Условные GET-запросы: ETag (из обработчика или SHA-256 тела), 304 по If-None-Match,
+// Cache-Control по политике для анонимных и авторизованных ответов. Ответы с ошибками и не-GET запросы не трогает
+pub struct HttpCacheMiddleware {
+    public: HeaderValue,
+    private: HeaderValue,
+}
+
+impl HttpCacheMiddleware {
+    pub fn new(config: &AppConfig) -> Self {
+        Self {
+            public: HeaderValue::from_str(&config.cache_control_public)
+                .expect("invalid CACHE_CONTROL_PUBLIC"),
+            private: HeaderValue::from_str(&config.cache_control_private)
+                .expect("invalid CACHE_CONTROL_PRIVATE"),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for HttpCacheMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = HttpCacheService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(HttpCacheService {
+            service,
+            public: self.public.clone(),
+            private: self.private.clone(),
+        }))
+    }
+}
+
+pub struct HttpCacheService<S> {
+    service: S,
+    public: HeaderValue,
+    private: HeaderValue,
+}
+
+impl<S, B> Service<ServiceRequest> for HttpCacheService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let cacheable = matches!(*req.method(), Method::GET | Method::HEAD);
+        // Ответ на запрос с токеном может зависеть от пользователя и не должен попадать в общие кэши
+        let cache_control = if req.headers().contains_key(header::AUTHORIZATION) {
+            self.private.clone()
+        } else {
+            self.public.clone()
+        };
+
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+            if !cacheable || !res.status().is_success() {
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (req, res) = res.into_parts();
+            let (mut res, body) = res.into_parts();
+            let body = body::to_bytes(body).await.map_err(|e| {
+                let e: Box<dyn std::error::Error> = e.into();
+                actix_web::error::ErrorInternalServerError(e.to_string())
+            })?;
+
+            let headers = res.headers_mut();
+            if !headers.contains_key(header::ETAG) {
+                let etag = body_etag(&body);
+                headers.insert(header::ETAG, HeaderValue::from_str(&etag.to_string())?);
+            }
+            if !headers.contains_key(header::CACHE_CONTROL) {
+                headers.insert(header::CACHE_CONTROL, cache_control);
+            }
+            headers.append(header::VARY, HeaderValue::from_static("Authorization"));
+
+            let etag = headers
+                .get(header::ETAG)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<EntityTag>().ok());
+
+            if is_not_modified(&req, etag.as_ref()) {
+                *res.status_mut() = StatusCode::NOT_MODIFIED;
+                res.headers_mut().remove(header::CONTENT_TYPE);
+                return Ok(ServiceResponse::new(req, res.set_body(BoxBody::new(()))));
+            }
+            Ok(ServiceResponse::new(req, res.set_body(BoxBody::new(body))))
+        })
+    }
+}
+
+// Сильный ETag по содержимому ответа
+pub fn body_etag(body: &[u8]) -> EntityTag {
+    let digest = Sha256::digest(body);
+    EntityTag::new_strong(digest[..16].iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// Last-Modified не выставляется: реакции, отметки читателя и соседние части серии
+// меняют ответ, не сдвигая updated_at, поэтому свежесть проверяется только по ETag
+pub fn is_not_modified(req: &HttpRequest, etag: Option<&EntityTag>) -> bool {
+    match req.get_header::<IfNoneMatch>() {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => {
+            etag.is_some_and(|etag| tags.iter().any(|tag| tag.weak_eq(etag)))
+        }
+        None => false,
+    }
+}
+
 pub struct JwtAuthMiddleware {
     keys: JwtKeys,
+    anonymous_reads: &'static [&'static str],
 }
 
 impl JwtAuthMiddleware {
     pub fn new(keys: JwtKeys) -> Self {
-        Self { keys }
+        Self {
+            keys,
+            anonymous_reads: &[],
+        }
+    }
+
+    // GET/HEAD без заголовка Authorization пропускаются без пользователя только для
+    // перечисленных шаблонов маршрутов (полный путь, как при регистрации), поэтому
+    // новый маршрут по умолчанию требует токен
+    pub fn allow_anonymous_reads(mut self, routes: &'static [&'static str]) -> Self {
+        self.anonymous_reads = routes;
+        self
     }
 }
 
@@ -186,6 +330,7 @@ where
         ready(Ok(JwtAuthService {
             service: Rc::new(RefCell::new(service)),
             keys: self.keys.clone(),
+            anonymous_reads: self.anonymous_reads,
         }))
     }
 }
@@ -193,6 +338,7 @@ where
 pub struct JwtAuthService<S> {
     service: Rc<RefCell<S>>,
     keys: JwtKeys,
+    anonymous_reads: &'static [&'static str],
 }
 
 impl<S, B> Service<ServiceRequest> for JwtAuthService<S>
@@ -222,16 +368,27 @@ where
             .and_then(|value| value.to_str().ok())
             .map(|value| value.to_string());
 
-        Box::pin(async move {
-            let auth_service = auth_service
-                .ok_or_else(|| actix_web::error::ErrorInternalServerError("AuthService missing"))?;
+        // Токен, если он передан, проверяется всегда: невалидный токен — 401, а не анонимный доступ
+        if auth_header.is_none()
+            && matches!(*req.method(), Method::GET | Method::HEAD)
+            && req
+                .match_pattern()
+                .is_some_and(|pattern| self.anonymous_reads.contains(&pattern.as_str()))
+        {
+            let fut = service.borrow_mut().call(req);
+            return Box::pin(fut);
+        }
 
+        Box::pin(async move {
             let header = auth_header
                 .ok_or_else(|| actix_web::error::ErrorUnauthorized("missing authorization header"))?;
             let token = header
                 .strip_prefix("Bearer ")
                 .ok_or_else(|| actix_web::error::ErrorUnauthorized("invalid authorization header"))?;
 
+            let auth_service = auth_service
+                .ok_or_else(|| actix_web::error::ErrorInternalServerError("AuthService missing"))?;
+
             let user = extract_user_from_token(token, &keys, auth_service.get_ref()).await?;
 
             req.extensions_mut().insert(user);
@@ -245,3 +402,50 @@ where
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::{test, App, HttpResponse};
+
+    #[actix_web::test]
+    async fn anonymous_reads_only_for_listed_routes() {
+        let app = test::init_service(
+            App::new().service(
+                web::scope("/api").service(
+                    web::scope("/post")
+                        .route("/{id}", web::get().to(HttpResponse::Ok))
+                        .route("/{id}/stats", web::get().to(HttpResponse::Ok))
+                        .route("/{id}", web::put().to(HttpResponse::Ok))
+                        .wrap(
+                            JwtAuthMiddleware::new(JwtKeys::new("secret".into()))
+                                .allow_anonymous_reads(&["/api/post/{id}"]),
+                        ),
+                ),
+            ),
+        )
+        .await;
+
+        let status = |req: test::TestRequest| {
+            let req = req.to_request();
+            let app = &app;
+            async move {
+                match app.call(req).await {
+                    Ok(res) => res.status(),
+                    Err(e) => e.as_response_error().status_code(),
+                }
+            }
+        };
+        assert_eq!(
+            status(test::TestRequest::get().uri("/api/post/1")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(test::TestRequest::get().uri("/api/post/1/stats")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(test::TestRequest::put().uri("/api/post/1")).await,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+}
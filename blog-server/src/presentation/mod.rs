@@ -3,3 +3,4 @@ pub mod grpc;
 pub mod middleware;
 pub mod dto;
 pub mod auth;
+pub mod syndication;
\ No newline at end of file
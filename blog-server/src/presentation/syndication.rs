@@ -0,0 +1,361 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{Map, Value, json};
+
+use crate::domain::markdown::{escape_html, plain_text};
+use crate::domain::post::Post;
+
+// Длина анонса в символах, если в ленты отдаётся не полный текст
+pub const EXCERPT_LEN: usize = 280;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl FeedFormat {
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        match name {
+            "rss.xml" => Some(Self::Rss),
+            "atom.xml" => Some(Self::Atom),
+            "feed.json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Rss => "application/rss+xml; charset=utf-8",
+            Self::Atom => "application/atom+xml; charset=utf-8",
+            Self::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+// Описание ленты: сайт целиком, автор или тег
+pub struct FeedChannel {
+    pub title: String,
+    pub description: String,
+    pub site_url: String,
+    // Ссылка на страницу поста, {slug} и {id} заменяются значениями поста
+    pub post_url_template: String,
+    // Абсолютный адрес самой ленты (rel="self")
+    pub feed_url: String,
+    pub author: String,
+    pub excerpts: bool,
+}
+
+impl FeedChannel {
+    fn post_url(&self, post: &Post) -> String {
+        self.post_url_template
+            .replace("{slug}", &post.slug)
+            .replace("{id}", &post.id.to_string())
+    }
+
+    // Не зависит от slug, поэтому не меняется при переименовании поста
+    fn post_id(&self, post: &Post) -> String {
+        format!("{}/api/post/{}", self.site_url, post.id)
+    }
+}
+
+pub fn post_date(post: &Post) -> DateTime<Utc> {
+    post.published_at.unwrap_or(post.created_at)
+}
+
+// Время последнего изменения ленты; у пустой ленты — начало эпохи
+fn last_modified(posts: &[Post]) -> DateTime<Utc> {
+    posts
+        .iter()
+        .map(|post| post.updated_at)
+        .max()
+        .unwrap_or(DateTime::UNIX_EPOCH)
+}
+
+pub fn render_feed(format: FeedFormat, channel: &FeedChannel, posts: &[Post]) -> String {
+    match format {
+        FeedFormat::Rss => render_rss(channel, posts),
+        FeedFormat::Atom => render_atom(channel, posts),
+        FeedFormat::Json => render_json_feed(channel, posts),
+    }
+}
+
+fn excerpt(post: &Post) -> String {
+    let text = plain_text(&post.content);
+    if text.chars().count() <= EXCERPT_LEN {
+        return text;
+    }
+    let cut: String = text.chars().take(EXCERPT_LEN).collect();
+    let cut = match cut.rfind(' ') {
+        Some(pos) if pos > 0 => &cut[..pos],
+        _ => &cut,
+    };
+    format!(
+        "{}…",
+        cut.trim_end_matches(|c: char| c.is_ascii_punctuation())
+    )
+}
+
+// RSS 2.0: даты в RFC 822, HTML в description экранируется
+fn render_rss(channel: &FeedChannel, posts: &[Post]) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="utf-8"?>"#);
+    xml.push_str(r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom"><channel>"#);
+    push_element(&mut xml, "title", &channel.title);
+    push_element(&mut xml, "link", &channel.site_url);
+    push_element(&mut xml, "description", &channel.description);
+    xml.push_str(&format!(
+        r#"<atom:link href="{}" rel="self" type="application/rss+xml"/>"#,
+        escape_html(&channel.feed_url)
+    ));
+    push_element(
+        &mut xml,
+        "lastBuildDate",
+        &last_modified(posts).to_rfc2822(),
+    );
+    for post in posts {
+        xml.push_str("<item>");
+        push_element(&mut xml, "title", &post.title);
+        push_element(&mut xml, "link", &channel.post_url(post));
+        xml.push_str(&format!(
+            r#"<guid isPermaLink="false">{}</guid>"#,
+            escape_html(&channel.post_id(post))
+        ));
+        push_element(&mut xml, "pubDate", &post_date(post).to_rfc2822());
+        for tag in &post.tags {
+            push_element(&mut xml, "category", tag);
+        }
+        let description = if channel.excerpts {
+            excerpt(post)
+        } else {
+            post.content_html.clone()
+        };
+        push_element(&mut xml, "description", &description);
+        xml.push_str("</item>");
+    }
+    xml.push_str("</channel></rss>");
+    xml
+}
+
+// Atom (RFC 4287): автор задаётся на уровне ленты, даты в RFC 3339
+fn render_atom(channel: &FeedChannel, posts: &[Post]) -> String {
+    let mut xml = String::from(r#"<?xml version="1.0" encoding="utf-8"?>"#);
+    xml.push_str(r#"<feed xmlns="http://www.w3.org/2005/Atom">"#);
+    push_element(&mut xml, "id", &channel.feed_url);
+    push_element(&mut xml, "title", &channel.title);
+    push_element(&mut xml, "subtitle", &channel.description);
+    push_element(&mut xml, "updated", &atom_date(last_modified(posts)));
+    xml.push_str(&format!(
+        r#"<link href="{}" rel="self" type="application/atom+xml"/><link href="{}"/>"#,
+        escape_html(&channel.feed_url),
+        escape_html(&channel.site_url)
+    ));
+    xml.push_str("<author>");
+    push_element(&mut xml, "name", &channel.author);
+    xml.push_str("</author>");
+    for post in posts {
+        xml.push_str("<entry>");
+        push_element(&mut xml, "id", &channel.post_id(post));
+        push_element(&mut xml, "title", &post.title);
+        xml.push_str(&format!(
+            r#"<link href="{}"/>"#,
+            escape_html(&channel.post_url(post))
+        ));
+        push_element(&mut xml, "published", &atom_date(post_date(post)));
+        push_element(&mut xml, "updated", &atom_date(post.updated_at));
+        for tag in &post.tags {
+            xml.push_str(&format!(r#"<category term="{}"/>"#, escape_html(tag)));
+        }
+        if channel.excerpts {
+            xml.push_str(r#"<summary type="text">"#);
+            xml.push_str(&xml_text(&excerpt(post)));
+            xml.push_str("</summary>");
+        } else {
+            xml.push_str(r#"<content type="html">"#);
+            xml.push_str(&xml_text(&post.content_html));
+            xml.push_str("</content>");
+        }
+        xml.push_str("</entry>");
+    }
+    xml.push_str("</feed>");
+    xml
+}
+
+// JSON Feed 1.1
+fn render_json_feed(channel: &FeedChannel, posts: &[Post]) -> String {
+    let items: Vec<Value> = posts
+        .iter()
+        .map(|post| {
+            let mut item = Map::new();
+            item.insert("id".into(), json!(channel.post_id(post)));
+            item.insert("url".into(), json!(channel.post_url(post)));
+            item.insert("title".into(), json!(post.title));
+            item.insert("date_published".into(), json!(atom_date(post_date(post))));
+            item.insert("date_modified".into(), json!(atom_date(post.updated_at)));
+            if !post.tags.is_empty() {
+                item.insert("tags".into(), json!(post.tags));
+            }
+            if channel.excerpts {
+                let summary = excerpt(post);
+                item.insert("summary".into(), json!(summary));
+                item.insert("content_text".into(), json!(summary));
+            } else {
+                item.insert("content_html".into(), json!(post.content_html));
+            }
+            Value::Object(item)
+        })
+        .collect();
+    let feed = json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": channel.title,
+        "home_page_url": channel.site_url,
+        "feed_url": channel.feed_url,
+        "description": channel.description,
+        "authors": [{ "name": channel.author }],
+        "items": items,
+    });
+    feed.to_string()
+}
+
+fn push_element(xml: &mut String, name: &str, text: &str) {
+    xml.push('<');
+    xml.push_str(name);
+    xml.push('>');
+    xml.push_str(&xml_text(text));
+    xml.push_str("</");
+    xml.push_str(name);
+    xml.push('>');
+}
+
+// Экранирует текст и выбрасывает управляющие символы, недопустимые в XML 1.0
+fn xml_text(text: &str) -> String {
+    let text: String = text
+        .chars()
+        .filter(|&c| {
+            matches!(c, '\t' | '\n' | '\r') || (c >= ' ' && c != '\u{FFFE}' && c != '\u{FFFF}')
+        })
+        .collect();
+    escape_html(&text)
+}
+
+fn atom_date(ts: DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::post::{CommentMode, PostStatus};
+
+    fn channel() -> FeedChannel {
+        FeedChannel {
+            title: "Blog & Co".into(),
+            description: "Notes".into(),
+            site_url: "https://blog.example".into(),
+            post_url_template: "https://blog.example/posts/{slug}".into(),
+            feed_url: "https://blog.example/feeds/rss.xml".into(),
+            author: "Blog".into(),
+            excerpts: false,
+        }
+    }
+
+    fn post() -> Post {
+        let published = DateTime::parse_from_rfc3339("2026-10-15T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let updated = DateTime::parse_from_rfc3339("2026-10-16T08:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        Post {
+            id: 7,
+            title: "<b>Rust</b> & \"friends\"\u{1}".into(),
+            content: "a & b".into(),
+            content_html: "<p>a &amp; b</p>".into(),
+            author_id: 1,
+            slug: "rust-friends".into(),
+            tags: vec!["rust".into(), "c&c".into()],
+            reactions: Vec::new(),
+            series: None,
+            comment_mode: CommentMode::Open,
+            status: PostStatus::Published,
+            created_at: published,
+            updated_at: updated,
+            published_at: Some(published),
+            publish_at: None,
+            deleted_at: None,
+            version: 1,
+        }
+    }
+
+    #[test]
+    fn renders_rss() {
+        let rss = render_feed(FeedFormat::Rss, &channel(), &[post()]);
+        assert_eq!(
+            rss,
+            concat!(
+                r#"<?xml version="1.0" encoding="utf-8"?>"#,
+                r#"<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom"><channel>"#,
+                "<title>Blog &amp; Co</title>",
+                "<link>https://blog.example</link>",
+                "<description>Notes</description>",
+                r#"<atom:link href="https://blog.example/feeds/rss.xml" rel="self" type="application/rss+xml"/>"#,
+                "<lastBuildDate>Fri, 16 Oct 2026 08:30:00 +0000</lastBuildDate>",
+                "<item>",
+                "<title>&lt;b&gt;Rust&lt;/b&gt; &amp; &quot;friends&quot;</title>",
+                "<link>https://blog.example/posts/rust-friends</link>",
+                r#"<guid isPermaLink="false">https://blog.example/api/post/7</guid>"#,
+                "<pubDate>Thu, 15 Oct 2026 12:00:00 +0000</pubDate>",
+                "<category>rust</category>",
+                "<category>c&amp;c</category>",
+                "<description>&lt;p&gt;a &amp;amp; b&lt;/p&gt;</description>",
+                "</item>",
+                "</channel></rss>",
+            )
+        );
+    }
+
+    #[test]
+    fn renders_atom() {
+        let atom = render_feed(FeedFormat::Atom, &channel(), &[post()]);
+        assert!(atom.starts_with(
+            r#"<?xml version="1.0" encoding="utf-8"?><feed xmlns="http://www.w3.org/2005/Atom">"#
+        ));
+        assert!(atom.ends_with("</entry></feed>"));
+        assert!(atom.contains("<updated>2026-10-16T08:30:00Z</updated><link href="));
+        assert!(atom.contains(concat!(
+            "<entry>",
+            "<id>https://blog.example/api/post/7</id>",
+            "<title>&lt;b&gt;Rust&lt;/b&gt; &amp; &quot;friends&quot;</title>",
+            r#"<link href="https://blog.example/posts/rust-friends"/>"#,
+            "<published>2026-10-15T12:00:00Z</published>",
+            "<updated>2026-10-16T08:30:00Z</updated>",
+            r#"<category term="rust"/><category term="c&amp;c"/>"#,
+            r#"<content type="html">&lt;p&gt;a &amp;amp; b&lt;/p&gt;</content>"#,
+        )));
+        assert!(!atom.contains('\u{1}'));
+    }
+
+    #[test]
+    fn renders_json_feed() {
+        let mut channel = channel();
+        channel.post_url_template = "https://blog.example/p/{id}".into();
+        let feed: Value =
+            serde_json::from_str(&render_feed(FeedFormat::Json, &channel, &[post()])).unwrap();
+        assert_eq!(feed["version"], "https://jsonfeed.org/version/1.1");
+        assert_eq!(feed["title"], "Blog & Co");
+        let item = &feed["items"][0];
+        assert_eq!(item["id"], "https://blog.example/api/post/7");
+        assert_eq!(item["url"], "https://blog.example/p/7");
+        // В JSON текст не экранируется как HTML
+        assert_eq!(item["title"], "<b>Rust</b> & \"friends\"\u{1}");
+        assert_eq!(item["content_html"], "<p>a &amp; b</p>");
+        assert_eq!(item["date_published"], "2026-10-15T12:00:00Z");
+        assert_eq!(item["tags"], json!(["rust", "c&c"]));
+        assert!(item.get("summary").is_none());
+
+        channel.excerpts = true;
+        let feed: Value =
+            serde_json::from_str(&render_feed(FeedFormat::Json, &channel, &[post()])).unwrap();
+        assert_eq!(feed["items"][0]["summary"], "a & b");
+        assert!(feed["items"][0].get("content_html").is_none());
+    }
+}